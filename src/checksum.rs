@@ -4,6 +4,7 @@ use std::convert::TryInto;
 use std::io::Read;
 use std::io::Write;
 
+use crate::structs::ChecksumAlgorithm;
 use crate::structs::ChecksumCliOptions;
 use crate::structs::ZakopaneError;
 
@@ -111,24 +112,60 @@ fn checksum_task_send_result(
     }
 }
 
-fn checksum_task_impl(path: std::path::PathBuf) -> ChecksumResult {
-    let mut hasher = crypto_hash::Hasher::new(crypto_hash::Algorithm::SHA256);
+// Wraps whichever digest implementation `ChecksumAlgorithm` selects
+// behind one write/finish interface, so `checksum_task_impl` doesn't
+// need to know which crate backs a given algorithm.
+enum DigestHasher {
+    Crypto(crypto_hash::Hasher),
+    Blake3(blake3::Hasher),
+}
+
+impl DigestHasher {
+    fn new(algorithm: ChecksumAlgorithm) -> DigestHasher {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => {
+                DigestHasher::Crypto(crypto_hash::Hasher::new(crypto_hash::Algorithm::SHA256))
+            }
+            ChecksumAlgorithm::Sha512 => {
+                DigestHasher::Crypto(crypto_hash::Hasher::new(crypto_hash::Algorithm::SHA512))
+            }
+            ChecksumAlgorithm::Blake3 => DigestHasher::Blake3(blake3::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) -> Result<(), ZakopaneError> {
+        match self {
+            DigestHasher::Crypto(hasher) => hasher.write_all(bytes).map_err(ZakopaneError::Io),
+            DigestHasher::Blake3(hasher) => {
+                hasher.update(bytes);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish_hex(self) -> String {
+        match self {
+            DigestHasher::Crypto(mut hasher) => hasher
+                .finish()
+                .into_iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<String>>()
+                .join(""),
+            DigestHasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+fn checksum_task_impl(path: std::path::PathBuf, algorithm: ChecksumAlgorithm) -> ChecksumResult {
+    let mut hasher = DigestHasher::new(algorithm);
     let mut buffer: Vec<u8> = vec![0; READ_SIZE];
     let mut file = std::fs::File::open(&path).map_err(ZakopaneError::Io)?;
     loop {
         let read_bytes = file.read(&mut buffer).map_err(ZakopaneError::Io)?;
         if read_bytes == 0 {
-            let checksum = hasher
-                .finish()
-                .into_iter()
-                .map(|byte| format!("{:02x}", byte))
-                .collect::<Vec<String>>()
-                .join("");
-            return Ok(ChecksumWithPath::new(checksum, path));
+            return Ok(ChecksumWithPath::new(hasher.finish_hex(), path));
         }
-        hasher
-            .write_all(&buffer[..read_bytes])
-            .map_err(ZakopaneError::Io)?;
+        hasher.update(&buffer[..read_bytes])?;
     }
 }
 
@@ -138,10 +175,11 @@ fn checksum_task_impl(path: std::path::PathBuf) -> ChecksumResult {
 // semaphore-dispensed resource for the duration of this task.
 fn checksum_task(
     path: std::path::PathBuf,
+    algorithm: ChecksumAlgorithm,
     sender: tokio::sync::mpsc::Sender<ChecksumResult>,
     _permit: tokio::sync::OwnedSemaphorePermit,
 ) {
-    let result = checksum_task_impl(path);
+    let result = checksum_task_impl(path, algorithm);
     checksum_task_send_result(result, sender);
 }
 
@@ -212,7 +250,8 @@ async fn spawn_checksum_tasks(context: ChecksumTaskDispatcherData) {
             .spawn_counter
             .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         let sender = context.sender.clone();
-        tokio::task::spawn_blocking(move || checksum_task(path, sender, permit));
+        let algorithm = context.cli_options.algorithm;
+        tokio::task::spawn_blocking(move || checksum_task(path, algorithm, sender, permit));
     }
 }
 
@@ -1,9 +1,13 @@
 use crate::config;
 use crate::snapshot;
 use crate::violations;
+use crate::violations::Severity;
 
 // Compares the older snapshot against the newer snapshot, accruing
-// violations where discrepancies are detected per policy.
+// violations where discrepancies are detected per policy. A change to
+// a path whose policy is `ignore` is still recorded, but only as
+// informational (`Severity::Info`), since it isn't an actual policy
+// breach.
 fn check_modifications_and_deletions(
     config: &config::Config,
     older_snapshot: &snapshot::Snapshot,
@@ -11,20 +15,24 @@ fn check_modifications_and_deletions(
     violations: &mut violations::Violations,
 ) {
     for (path, checksum) in older_snapshot.iter() {
-        let (_rule_repr, policy) = config.match_policy(path);
-        if policy == config::POLICY_IGNORE {
-            continue;
-        }
+        let policy = config.match_policy(path);
 
         match newer_snapshot.get(path) {
             Some(newer_checksum) => {
-                if (policy & config::POLICY_NOMODIFY) != 0 && checksum != newer_checksum {
-                    violations.modified(path);
+                if checksum == newer_checksum {
+                    continue;
+                }
+                if policy.is_ignore() {
+                    violations.modified(path, Severity::Info, checksum, newer_checksum);
+                } else if policy.is_nomodify() {
+                    violations.modified(path, Severity::Error, checksum, newer_checksum);
                 }
             }
             None => {
-                if (policy & config::POLICY_NODELETE) != 0 {
-                    violations.deleted(path);
+                if policy.is_ignore() {
+                    violations.deleted(path, Severity::Info);
+                } else if policy.is_nodelete() {
+                    violations.deleted(path, Severity::Error);
                 }
             }
         }
@@ -32,7 +40,9 @@ fn check_modifications_and_deletions(
 }
 
 // Compares the newer snapshot against the older snapshot, accruing
-// violations where discrepancies are detected per policy.
+// violations where discrepancies are detected per policy. As above, a
+// change to an `ignore`d path is recorded informationally rather than
+// suppressed outright.
 fn check_additions(
     config: &config::Config,
     older_snapshot: &snapshot::Snapshot,
@@ -40,16 +50,15 @@ fn check_additions(
     violations: &mut violations::Violations,
 ) {
     for (path, _checksum) in newer_snapshot.iter() {
-        let (_rule_repr, policy) = config.match_policy(path);
-        if policy == config::POLICY_IGNORE {
-            continue;
-        }
+        let policy = config.match_policy(path);
 
         match older_snapshot.get(path) {
             Some(_older_checksum) => (),
             None => {
-                if (policy & config::POLICY_NOADD) != 0 {
-                    violations.added(path);
+                if policy.is_ignore() {
+                    violations.added(path, Severity::Info);
+                } else if policy.is_noadd() {
+                    violations.added(path, Severity::Error);
                 }
             }
         }
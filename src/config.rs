@@ -2,9 +2,11 @@
 // configuration files.
 
 use std::clone::Clone;
+use std::collections::HashMap;
 
 use yaml_rust::{Yaml, YamlLoader};
 
+use crate::matcher::Matcher;
 use crate::structs::ZakopaneError;
 
 type PolicyBitfield = u8;
@@ -37,16 +39,59 @@ fn policy_int_from(token: &str) -> Result<PolicyBitfield, ZakopaneError> {
 impl TryFrom<&str> for Policy {
     type Error = crate::structs::ZakopaneError;
     fn try_from(input: &str) -> Result<Self, Self::Error> {
-        let policy_u8s: Vec<PolicyBitfield> = input
-            .split(",")
-            .map(|tok| policy_int_from(tok))
-            .collect::<Result<Vec<PolicyBitfield>, ZakopaneError>>(
-        )?;
-        let folded = policy_u8s
-            .iter()
-            .fold(PolicyAsU8::Ignore as u8, |accum, elem| accum | elem);
-        Ok(Policy { bitfield: folded })
+        policy_from_tokens(input, &Definitions::new(), &mut Vec::new())
+    }
+}
+
+// Maps a config's `definitions` alias names to their (still
+// unresolved) token expressions, e.g. `{"archive": "noadd,nodelete"}`.
+type Definitions = HashMap<String, String>;
+
+// Resolves a comma-separated policy token expression into a Policy.
+// A token that isn't one of the built-in keywords (`ignore`, `noadd`,
+// etc.) is looked up as an alias in `definitions`; `resolving` tracks
+// the chain of alias names currently being expanded so a
+// self-referential alias is rejected instead of recursing forever.
+fn policy_from_tokens(
+    expr: &str,
+    definitions: &Definitions,
+    resolving: &mut Vec<String>,
+) -> Result<Policy, ZakopaneError> {
+    let policy_u8s: Vec<PolicyBitfield> = expr
+        .split(",")
+        .map(|token| token_bitfield(token, definitions, resolving))
+        .collect::<Result<Vec<PolicyBitfield>, ZakopaneError>>()?;
+    let folded = policy_u8s
+        .iter()
+        .fold(PolicyAsU8::Ignore as u8, |accum, elem| accum | elem);
+    Ok(Policy { bitfield: folded })
+}
+
+// Resolves a single policy token to its bitfield, either directly (a
+// built-in keyword) or by expanding it as an alias defined in
+// `definitions`.
+fn token_bitfield(
+    token: &str,
+    definitions: &Definitions,
+    resolving: &mut Vec<String>,
+) -> Result<PolicyBitfield, ZakopaneError> {
+    if let Ok(bitfield) = policy_int_from(token) {
+        return Ok(bitfield);
+    }
+    if resolving.iter().any(|name| name == token) {
+        resolving.push(token.to_owned());
+        return Err(ZakopaneError::Config(format!(
+            "self-referential definition: {}",
+            resolving.join(" -> ")
+        )));
     }
+    let alias_expr = definitions
+        .get(token)
+        .ok_or_else(|| ZakopaneError::Config(format!("bad token: ``{}''", token)))?;
+    resolving.push(token.to_owned());
+    let resolved = policy_from_tokens(alias_expr, definitions, resolving)?;
+    resolving.pop();
+    Ok(resolved.bitfield)
 }
 
 impl Policy {
@@ -65,12 +110,15 @@ impl Policy {
 }
 
 // Represents a sorted vector of zakopane config rules, each mapping a
-// path (prefix) to a policy. This type alias is provided for ease of
-// coding.
-type Policies = Vec<(String, Policy)>;
+// compiled policy key (a literal prefix, a glob pattern, or an
+// anchored regex) to a policy. This type alias is provided for ease
+// of coding.
+type Policies = Vec<(Matcher, Policy)>;
 
 const DEFAULT_POLICY_KEY: &'static str = "default-policy";
 const POLICIES_KEY: &'static str = "policies";
+const INCLUDE_KEY: &'static str = "include";
+const DEFINITIONS_KEY: &'static str = "definitions";
 
 // Represents a zakopane config. Please consult the documentation.
 pub struct Config {
@@ -84,22 +132,26 @@ pub struct Config {
 fn policy_tuple_from_yaml(
     ypath: &Yaml,
     policy_tokens: &Yaml,
-) -> Result<(String, Policy), ZakopaneError> {
+    definitions: &Definitions,
+) -> Result<(Matcher, Policy), ZakopaneError> {
     let path: String = match ypath.as_str() {
         Some(string) => string.to_owned(),
         None => return Err(ZakopaneError::Config("malformed path?".to_string())),
     };
     let policy: Policy = match policy_tokens.as_str() {
-        Some(string) => Policy::try_from(string)?,
+        Some(string) => policy_from_tokens(string, definitions, &mut Vec::new())
+            .map_err(|error| ZakopaneError::Config(format!("rule `{}`: {}", path, error)))?,
         None => return Err(ZakopaneError::Config("malformed policy?".to_string())),
     };
-    Ok((path, policy))
+    let matcher = Matcher::compile(&path)
+        .map_err(|error| ZakopaneError::Config(format!("rule `{}`: {}", path, error)))?;
+    Ok((matcher, policy))
 }
 
 // Borrows the YAML representation of a zakopane config and returns the
 // corresponding Policies. The return value can be benignly
 // empty (e.g. if the present config elects not to specify any rules).
-fn policies_from_yaml(doc: &Yaml) -> Result<Policies, ZakopaneError> {
+fn policies_from_yaml(doc: &Yaml, definitions: &Definitions) -> Result<Policies, ZakopaneError> {
     let policies_map_yaml = &doc[POLICIES_KEY];
     if policies_map_yaml.is_badvalue() {
         // Assumes the config may be benignly devoid of specific
@@ -107,34 +159,104 @@ fn policies_from_yaml(doc: &Yaml) -> Result<Policies, ZakopaneError> {
         return Ok(vec![]);
     }
     // Otherwise, iterates over the policies map. Each entry in the
-    // policies map correlates a path prefix to a comma-separated list
-    // of policies.
+    // policies map correlates a path key (prefix, glob, or regex) to
+    // a comma-separated list of policies.
     let policies_map: &yaml_rust::yaml::Hash = match policies_map_yaml.as_hash() {
         Some(map) => map,
         None => return Err(ZakopaneError::Config("malformed policies".to_string())),
     };
-    let mut policies: Policies = policies_map
-        .into_iter()
-        .map(|pair| policy_tuple_from_yaml(&pair.0, &pair.1))
-        .collect::<Result<Policies, ZakopaneError>>()?;
-    policies.sort_unstable_by_key(|pair| pair.0.to_owned());
+    // Sorts by the raw YAML key before compiling, since a compiled
+    // Matcher (in particular a Regex) has no single sortable
+    // representation; this is what gives `match_policy` a
+    // deterministic tie-break order.
+    let mut entries: Vec<(&Yaml, &Yaml)> = policies_map.into_iter().collect();
+    entries.sort_unstable_by(|a, b| a.0.as_str().unwrap_or("").cmp(b.0.as_str().unwrap_or("")));
+
+    // Walks every entry regardless of earlier failures, so a config
+    // with several malformed rules gets reported all at once instead
+    // of one edit-run cycle per typo.
+    let mut policies = Policies::new();
+    let mut errors = Vec::new();
+    for (ypath, policy_tokens) in entries {
+        match policy_tuple_from_yaml(ypath, policy_tokens, definitions) {
+            Ok(tuple) => policies.push(tuple),
+            Err(error) => errors.push(error),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ZakopaneError::Multiple(errors));
+    }
     Ok(policies)
 }
 
 // Borrows the YAML representation of a zakopane config and returns the
 // integral default-policy defined within.
-fn default_policy_from_yaml(doc: &Yaml) -> Result<Option<Policy>, ZakopaneError> {
+fn default_policy_from_yaml(
+    doc: &Yaml,
+    definitions: &Definitions,
+) -> Result<Option<Policy>, ZakopaneError> {
     let default_policy_yaml = &doc[DEFAULT_POLICY_KEY];
     if default_policy_yaml.is_badvalue() {
         return Ok(None);
     }
     let default_policy: Policy = match default_policy_yaml.as_str() {
         None => return Err(ZakopaneError::Config(DEFAULT_POLICY_KEY.to_string())),
-        Some(token) => Policy::try_from(token),
+        Some(token) => policy_from_tokens(token, definitions, &mut Vec::new()),
     }?;
     Ok(Some(default_policy))
 }
 
+// Borrows the YAML representation of a zakopane config and returns its
+// top-level `definitions` map of alias name to (still unresolved)
+// token expression. The return value can be benignly empty.
+fn definitions_from_yaml(doc: &Yaml) -> Result<Definitions, ZakopaneError> {
+    let definitions_yaml = &doc[DEFINITIONS_KEY];
+    if definitions_yaml.is_badvalue() {
+        return Ok(Definitions::new());
+    }
+    let definitions_map: &yaml_rust::yaml::Hash = match definitions_yaml.as_hash() {
+        Some(map) => map,
+        None => return Err(ZakopaneError::Config("malformed definitions".to_string())),
+    };
+
+    let mut definitions = Definitions::new();
+    for (yname, yexpr) in definitions_map {
+        let name = yname
+            .as_str()
+            .ok_or_else(|| ZakopaneError::Config("malformed definition name".to_string()))?;
+        let expr = yexpr
+            .as_str()
+            .ok_or_else(|| ZakopaneError::Config("malformed definition value".to_string()))?;
+        definitions.insert(name.to_owned(), expr.to_owned());
+    }
+    Ok(definitions)
+}
+
+// Borrows the YAML representation of a zakopane config and returns the
+// paths named by its top-level `include` key, resolved relative to
+// `base_dir` (the including file's own directory). The return value is
+// benignly empty if the config doesn't include anything.
+fn include_paths_from_yaml(
+    doc: &Yaml,
+    base_dir: &std::path::Path,
+) -> Result<Vec<std::path::PathBuf>, ZakopaneError> {
+    let include_yaml = &doc[INCLUDE_KEY];
+    if include_yaml.is_badvalue() {
+        return Ok(vec![]);
+    }
+    let entries = match include_yaml.as_vec() {
+        Some(entries) => entries,
+        None => return Err(ZakopaneError::Config("malformed include".to_string())),
+    };
+    entries
+        .iter()
+        .map(|entry| match entry.as_str() {
+            Some(relative_path) => Ok(base_dir.join(relative_path)),
+            None => Err(ZakopaneError::Config("malformed include entry".to_string())),
+        })
+        .collect()
+}
+
 // Interprets |config_contents| as YAML and returns the first document
 // within (if present).
 fn read_yaml(config_contents: &str) -> Result<Option<Yaml>, ZakopaneError> {
@@ -148,44 +270,90 @@ fn read_yaml(config_contents: &str) -> Result<Option<Yaml>, ZakopaneError> {
     Ok(Some(docs[0].clone()))
 }
 
-// Returns the default policy for this invocation.
-fn get_default_policy(yaml_config: &Option<Yaml>) -> Result<Policy, ZakopaneError> {
-    if let Some(yaml) = yaml_config {
-        if let Some(default_policy) = default_policy_from_yaml(&yaml)? {
-            return Ok(default_policy);
-        }
-    }
-    Ok(Policy {
-        bitfield: PolicyAsU8::Immutable as u8,
-    })
-}
-
 // Returns any additional policies for this invocation.
-fn get_policies(yaml_config: &Option<Yaml>) -> Result<Policies, ZakopaneError> {
+fn get_policies(
+    yaml_config: &Option<Yaml>,
+    definitions: &Definitions,
+) -> Result<Policies, ZakopaneError> {
     match yaml_config {
-        Some(doc) => policies_from_yaml(doc),
+        Some(doc) => policies_from_yaml(doc, definitions),
         None => Ok(Policies::new()),
     }
 }
 
+// Loads `path`, merges its includes (relative to `path`'s own
+// directory) in first, then appends its own policies and applies its
+// own default-policy (if any) on top. `loading` tracks the chain of
+// paths currently being loaded so cyclic includes can be detected
+// instead of recursing forever. A file's `definitions` aliases are
+// only ever visible to that same file's own `policies`/`default-policy`
+// entries, not to files that include it or that it includes.
+fn load_config_file(
+    path: &std::path::PathBuf,
+    loading: &mut Vec<std::path::PathBuf>,
+    default_policy: &mut Policy,
+    policies: &mut Policies,
+) -> Result<(), ZakopaneError> {
+    if loading.contains(path) {
+        let cycle: Vec<String> = loading
+            .iter()
+            .chain(std::iter::once(path))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(ZakopaneError::Config(format!(
+            "cyclic include: {}",
+            cycle.join(" -> ")
+        )));
+    }
+    loading.push(path.clone());
+
+    let config_contents = crate::helpers::ingest_file(path)?;
+    let yaml_config = read_yaml(&config_contents)?;
+
+    if let Some(yaml) = &yaml_config {
+        let base_dir = path
+            .parent()
+            .map(std::path::Path::to_path_buf)
+            .unwrap_or_default();
+        for include_path in include_paths_from_yaml(yaml, &base_dir)? {
+            load_config_file(&include_path, loading, default_policy, policies)?;
+        }
+    }
+
+    let definitions = match &yaml_config {
+        Some(yaml) => definitions_from_yaml(yaml)?,
+        None => Definitions::new(),
+    };
+    if let Some(yaml) = &yaml_config {
+        if let Some(overriding_default) = default_policy_from_yaml(yaml, &definitions)? {
+            *default_policy = overriding_default;
+        }
+    }
+    policies.extend(get_policies(&yaml_config, &definitions)?);
+
+    loading.pop();
+    Ok(())
+}
+
 impl Config {
-    // Borrows the string representation of a zakopane config and
-    // returns a corresponding Config.
-    pub fn new(config_path: Option<std::path::PathBuf>) -> Result<Config, ZakopaneError> {
-        let yaml_config: Option<Yaml> = match config_path {
-            Some(path) => {
-                let config = crate::helpers::ingest_file(&path)?;
-                read_yaml(&config)?
-            }
-            None => None,
+    // Accepts the paths of one or more zakopane config files, reads
+    // each, and returns the single Config they merge into. Later files
+    // (and files reached via an earlier file's `include` key) layer on
+    // top: their `policies` entries are appended, and their
+    // `default-policy` (if set) overrides whatever came before.
+    pub fn new(config_paths: Vec<std::path::PathBuf>) -> Result<Config, ZakopaneError> {
+        let mut default_policy = Policy {
+            bitfield: PolicyAsU8::Immutable as u8,
         };
-
-        let default_policy = get_default_policy(&yaml_config)?;
-        let policies = get_policies(&yaml_config)?;
+        let mut policies = Policies::new();
+        let mut loading = Vec::new();
+        for path in config_paths {
+            load_config_file(&path, &mut loading, &mut default_policy, &mut policies)?;
+        }
 
         Ok(Config {
-            default_policy: default_policy,
-            policies: policies,
+            default_policy,
+            policies,
         })
     }
 
@@ -195,12 +363,24 @@ impl Config {
         1 + self.policies.len()
     }
 
+    // Finds the most specific policy matcher matching `path` (plain
+    // prefix, glob, or regex) and returns its Policy, falling back to
+    // the default policy if nothing matches. Overlapping matches are
+    // resolved by `Matcher::specificity`: the first match found
+    // always wins unless a later one is strictly more specific, so
+    // ties between equally-specific matchers favor whichever comes
+    // first: within one file, whichever sorts first (see
+    // `policies_from_yaml`'s sort); across files, whichever file was
+    // loaded earlier.
     pub fn match_policy(&self, path: &str) -> &Policy {
-        let mut best_match_path: &str = "";
+        let mut best_specificity: usize = 0;
         let mut best_match_policy: Option<&Policy> = None;
-        for (prefix, policy) in self.policies.iter() {
-            if path.starts_with(prefix) && prefix.len() > best_match_path.len() {
-                best_match_path = prefix;
+        for (matcher, policy) in self.policies.iter() {
+            if !matcher.matches(path) {
+                continue;
+            }
+            if best_match_policy.is_none() || matcher.specificity() > best_specificity {
+                best_specificity = matcher.specificity();
                 best_match_policy = Some(policy);
             }
         }
@@ -257,7 +437,7 @@ mod tests {
     fn config_can_contain_anything() {
         // This...might not be the best behavior to go for.
         let config_path = test_support::data_path("flagrantly-invalid-yaml");
-        let config = Config::new(Some(config_path)).unwrap();
+        let config = Config::new(vec![config_path]).unwrap();
         assert_eq!(config.rules(), 1);
     }
 
@@ -265,7 +445,7 @@ mod tests {
     fn config_can_be_empty() {
         // An empty config file is valid (albeit trivial) YAML and is
         // considered valid.
-        let config = Config::new(Some(std::path::PathBuf::from("/dev/null"))).unwrap();
+        let config = Config::new(vec![std::path::PathBuf::from("/dev/null")]).unwrap();
 
         assert!(config.default_policy.is_noadd());
         assert!(config.default_policy.is_nodelete());
@@ -276,7 +456,7 @@ mod tests {
     fn config_can_omit_default_policy() {
         // A config file without a default policy is valid.
         let config_path = test_support::data_path("config-without-default-policy");
-        let config = Config::new(Some(config_path)).unwrap();
+        let config = Config::new(vec![config_path]).unwrap();
         assert_eq!(config.rules(), 5);
 
         assert!(config.default_policy.is_noadd());
@@ -287,7 +467,7 @@ mod tests {
     #[test]
     fn config_might_not_have_specific_policies() {
         let config_path = test_support::data_path("config-without-specific-policies");
-        let config = Config::new(Some(config_path)).unwrap();
+        let config = Config::new(vec![config_path]).unwrap();
         assert!(config.rules() == 1);
         assert!(config.match_policy("").is_nodelete());
     }
@@ -295,13 +475,13 @@ mod tests {
     #[test]
     fn config_policies_must_be_a_map() {
         let config_path = test_support::data_path("config-with-ill-formed-policies");
-        assert!(Config::new(Some(config_path)).is_err());
+        assert!(Config::new(vec![config_path]).is_err());
     }
 
     #[test]
     fn match_default_policy() {
         let config_path = test_support::data_path("config-without-specific-policies");
-        let config = Config::new(Some(config_path)).unwrap();
+        let config = Config::new(vec![config_path]).unwrap();
 
         // With only a default policy, this config has just 1 rule.
         assert_eq!(config.rules(), 1);
@@ -319,7 +499,7 @@ mod tests {
     #[test]
     fn match_nondefault_policies() {
         let config_path = test_support::data_path("config-with-several-policies");
-        let config = Config::new(Some(config_path)).unwrap();
+        let config = Config::new(vec![config_path]).unwrap();
 
         assert_eq!(config.rules(), 5);
 
@@ -348,4 +528,68 @@ mod tests {
         assert!(policy.is_nodelete());
         assert!(policy.is_nomodify());
     }
+
+    #[test]
+    fn config_merges_multiple_files() {
+        // The later file's default-policy wins, but both files'
+        // policies entries are kept.
+        let base = test_support::data_path("config-without-default-policy");
+        let overrides = test_support::data_path("config-without-specific-policies");
+        let config = Config::new(vec![base, overrides]).unwrap();
+
+        assert_eq!(config.rules(), 5);
+        assert!(config.match_policy("").is_nodelete());
+    }
+
+    #[test]
+    fn config_include_merges_relative_to_including_file() {
+        let config_path = test_support::data_path("config-with-include");
+        let config = Config::new(vec![config_path]).unwrap();
+
+        // The included file's `./Pictures` rule is visible, plus the
+        // including file's own default-policy override.
+        assert!(config.match_policy("./Pictures/2016/yano.jpg").is_noadd());
+        assert!(config.match_policy("./Documents/report.txt").is_ignore());
+    }
+
+    #[test]
+    fn config_detects_cyclic_include() {
+        let config_path = test_support::data_path("config-cyclic-include-a");
+        assert!(Config::new(vec![config_path]).is_err());
+    }
+
+    #[test]
+    fn policy_token_resolves_alias() {
+        let mut definitions = Definitions::new();
+        definitions.insert("archive".to_string(), "noadd,nodelete".to_string());
+
+        let policy = policy_from_tokens("archive,nomodify", &definitions, &mut Vec::new()).unwrap();
+        assert!(policy.is_noadd());
+        assert!(policy.is_nodelete());
+        assert!(policy.is_nomodify());
+    }
+
+    #[test]
+    fn policy_token_rejects_undefined_alias() {
+        let definitions = Definitions::new();
+        assert!(policy_from_tokens("archive", &definitions, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn policy_token_rejects_self_referential_alias() {
+        let mut definitions = Definitions::new();
+        definitions.insert("archive".to_string(), "archive".to_string());
+
+        assert!(policy_from_tokens("archive", &definitions, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn config_resolves_definitions_in_policies_and_default_policy() {
+        let config_path = test_support::data_path("config-with-definitions");
+        let config = Config::new(vec![config_path]).unwrap();
+
+        assert!(config.default_policy.is_noadd());
+        assert!(config.default_policy.is_nodelete());
+        assert!(config.match_policy("./Pictures/2016/yano.jpg").is_ignore());
+    }
 }
@@ -1,9 +1,12 @@
 pub mod checksum;
 mod compare;
 pub mod config;
+mod glob;
 pub mod helpers;
+mod matcher;
 pub mod snapshot;
 pub mod structs;
 pub mod violations;
 
+pub use checksum::checksum;
 pub use compare::compare;
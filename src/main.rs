@@ -2,15 +2,18 @@ use std::io::Write;
 
 use libzakopane::config::Config;
 use libzakopane::snapshot::Snapshot;
+use libzakopane::structs::ChecksumCliOptions;
 use libzakopane::structs::ZakopaneError;
 
 fn generate_snapshot_header(
     path: &std::path::PathBuf,
     start_time: &chrono::DateTime<chrono::offset::Local>,
+    algorithm: libzakopane::structs::ChecksumAlgorithm,
 ) -> String {
     let buffer: Vec<String> = vec![
         format!("zakopane: {}", start_time),
         format!("zakopane: {}", path.display()),
+        format!("zakopane-algorithm: {}", algorithm.snapshot_token()),
         String::new(),
         String::new(),
     ];
@@ -23,13 +26,22 @@ fn do_checksum(args: libzakopane::structs::ChecksumArgs) {
         eprintln!("``{}'' is not a dir", args.target.display());
         return;
     }
-    let start_time = chrono::offset::Local::now();
-    println!("checksum ``{}'' at {start_time}", args.target.display(),);
-    let mut output = std::fs::File::create(&args.output_path).unwrap();
+    let options = ChecksumCliOptions::new(
+        args.target,
+        Some(args.output_path),
+        args.max_tasks,
+        args.big_file_bytes,
+        args.hash,
+    )
+    .unwrap();
+
+    let start_time = options.start_time;
+    println!("checksum ``{}'' at {start_time}", options.path.display());
+    let mut output = std::fs::File::create(&options.output_path).unwrap();
 
-    let header = generate_snapshot_header(&args.target, &start_time);
-    let output_path = args.output_path.clone();
-    let checksums = libzakopane::checksum(args);
+    let header = generate_snapshot_header(&options.path, &start_time, options.algorithm);
+    let output_path = options.output_path.clone();
+    let checksums = libzakopane::checksum(options);
 
     output.write_all(header.as_ref()).unwrap();
     output.write_all(checksums.as_ref()).unwrap();
@@ -42,30 +54,52 @@ fn do_checksum(args: libzakopane::structs::ChecksumArgs) {
     );
 }
 
-fn do_compare(args: libzakopane::structs::CompareArgs) {
+// Runs the `compare` subcommand and returns the process exit code: 1
+// if `--fail-on` is set and the worst Error-severity violation found
+// reaches at least that kind, 0 otherwise.
+fn do_compare(args: libzakopane::structs::CompareArgs) -> i32 {
+    use libzakopane::structs::OutputFormat;
+
     let config = Config::new(args.config).unwrap();
     let old_snapshot = Snapshot::new(
-        libzakopane::helpers::ingest_file(args.old_snapshot)
+        libzakopane::helpers::ingest_file(&args.old_snapshot)
             .unwrap()
             .as_str(),
     )
     .unwrap();
     let new_snapshot = Snapshot::new(
-        libzakopane::helpers::ingest_file(args.new_snapshot)
+        libzakopane::helpers::ingest_file(&args.new_snapshot)
             .unwrap()
             .as_str(),
     )
     .unwrap();
+    libzakopane::snapshot::ensure_same_algorithm(&old_snapshot, &new_snapshot).unwrap();
     let violations = libzakopane::compare(&config, &old_snapshot, &new_snapshot);
-    println!("{}", violations);
+
+    if args.problem_matcher {
+        print!("{}", violations.to_problem_matcher());
+    }
+    match args.format {
+        OutputFormat::Text => println!("{}", violations),
+        OutputFormat::Json => println!("{}", violations.to_json()),
+    }
+
+    match (args.fail_on, violations.worst_error_kind()) {
+        (Some(threshold), Some(worst)) if worst >= threshold => 1,
+        _ => 0,
+    }
 }
 
 fn main() {
     use clap::Parser;
     use libzakopane::structs::{Cli, Subcommand};
     let cli = Cli::parse();
-    match cli.subcommand {
-        Subcommand::Checksum(args) => do_checksum(args),
+    let exit_code = match cli.subcommand {
+        Subcommand::Checksum(args) => {
+            do_checksum(args);
+            0
+        }
         Subcommand::Compare(args) => do_compare(args),
-    }
+    };
+    std::process::exit(exit_code);
 }
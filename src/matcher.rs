@@ -0,0 +1,136 @@
+// This module defines `Matcher`, the compiled form of a zakopane
+// policy key. A key is one of:
+//   - a plain literal prefix (today's default, matched with
+//     `starts_with`);
+//   - a glob pattern (see `crate::glob`), detected by the presence of
+//     `*`, `?`, or `[`;
+//   - an anchored regular expression, written with a `re:` prefix.
+// Compiling a key once (at `Config::new` time) means
+// `Config::match_policy` never re-parses a pattern while walking a
+// snapshot.
+
+use crate::glob;
+use crate::structs::ZakopaneError;
+
+const REGEX_PREFIX: &str = "re:";
+
+pub enum Matcher {
+    Prefix(String),
+    Glob(glob::Pattern),
+    Regex(regex::Regex),
+}
+
+// Counts the characters in `s` that aren't one of `metacharacters`,
+// for use as a pattern's specificity score: the more literal
+// characters a pattern pins down, the more specific it is. `-` is
+// only ever a metacharacter inside a `[...]` character class (e.g.
+// `[a-z]`); a literal `-` elsewhere (as in `foo-bar`) always counts
+// as literal, even when `metacharacters` includes it.
+fn literal_char_count(s: &str, metacharacters: &str) -> usize {
+    let dash_is_metacharacter = metacharacters.contains('-');
+    let mut in_class = false;
+    let mut count = 0;
+    for c in s.chars() {
+        let is_metacharacter = if c == '-' {
+            dash_is_metacharacter && in_class
+        } else {
+            metacharacters.contains(c)
+        };
+        if c == '[' {
+            in_class = true;
+        } else if c == ']' {
+            in_class = false;
+        }
+        if !is_metacharacter {
+            count += 1;
+        }
+    }
+    count
+}
+
+impl Matcher {
+    pub fn compile(key: &str) -> Result<Matcher, ZakopaneError> {
+        if let Some(pattern) = key.strip_prefix(REGEX_PREFIX) {
+            let anchored = format!("^{}", pattern);
+            let regex = regex::Regex::new(&anchored).map_err(|e| {
+                ZakopaneError::Config(format!("bad regex ``{}'': {}", pattern, e))
+            })?;
+            return Ok(Matcher::Regex(regex));
+        }
+        if key.contains(['*', '?', '['].as_ref()) {
+            return Ok(Matcher::Glob(glob::Pattern::compile(key)));
+        }
+        Ok(Matcher::Prefix(key.to_owned()))
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        match self {
+            Matcher::Prefix(prefix) => path.starts_with(prefix.as_str()),
+            Matcher::Glob(pattern) => pattern.matches(path),
+            Matcher::Regex(regex) => regex.is_match(path),
+        }
+    }
+
+    // A pattern's specificity is its count of literal characters; a
+    // plain prefix's every character is literal, so it scores its
+    // full length exactly as today's prefix-length comparison did.
+    // Ties are broken by the order `policies_from_yaml` produces,
+    // which is sorted for determinism.
+    pub fn specificity(&self) -> usize {
+        match self {
+            Matcher::Prefix(prefix) => prefix.len(),
+            Matcher::Glob(pattern) => literal_char_count(pattern.key(), "*?[]!^-"),
+            Matcher::Regex(regex) => {
+                literal_char_count(regex.as_str(), ".*+?()[]{}^$|\\")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_matches_and_scores_by_length() {
+        let matcher = Matcher::compile("./Pictures").unwrap();
+        assert!(matcher.matches("./Pictures/2020/food.md"));
+        assert!(!matcher.matches("./Music/2020/food.md"));
+        assert_eq!(matcher.specificity(), "./Pictures".len());
+    }
+
+    #[test]
+    fn glob_key_is_detected_and_scored_by_literal_chars() {
+        let broad = Matcher::compile("./Pictures/**/*.jpg").unwrap();
+        let narrow = Matcher::compile("./Pictures/2020/*.jpg").unwrap();
+        assert!(broad.matches("./Pictures/2020/summer/yano.jpg"));
+        assert!(!broad.matches("./Pictures/2020/summer/yano.png"));
+        // More literal characters pin the path down more precisely, so
+        // the narrower pattern should outscore the broader one.
+        assert!(narrow.specificity() > broad.specificity());
+    }
+
+    #[test]
+    fn regex_key_is_anchored_and_scored_by_literal_chars() {
+        let matcher = Matcher::compile(r"re:\./Pictures/\d+/.*\.jpg").unwrap();
+        assert!(matcher.matches("./Pictures/2020/yano.jpg"));
+        // Anchored at the start, so a mid-string match doesn't count.
+        assert!(!matcher.matches("./old/./Pictures/2020/yano.jpg"));
+    }
+
+    #[test]
+    fn bad_regex_is_rejected() {
+        assert!(Matcher::compile("re:[").is_err());
+    }
+
+    #[test]
+    fn literal_dash_outside_class_counts_toward_specificity() {
+        let dashed = Matcher::compile("./foo-bar/*.jpg").unwrap();
+        let undashed = Matcher::compile("./foobar/*.jpg").unwrap();
+        // "foo-bar" has one more literal character than "foobar" (the
+        // `-` itself), so it must score exactly one higher - if `-`
+        // were (wrongly) stripped as a metacharacter outside a `[...]`
+        // class, the two patterns would tie instead.
+        assert_eq!(dashed.specificity(), undashed.specificity() + 1);
+    }
+}
@@ -1,26 +1,32 @@
 // This module implements the snapshot files used by zakopane.
 // ATOW a snapshot file is pretty much the output of the ``sha256sum''
-// command with three extra lines atop.
+// command with four extra lines atop.
 
+use crate::structs::ChecksumAlgorithm;
 use crate::structs::ZakopaneError;
 
 // Defines the number of lines preceding the actual checksum content.
-const HEADER_LINES: usize = 3;
+const HEADER_LINES: usize = 4;
 
-// Defines the number of hex characters in a sha256sum.
-const CHECKSUM_CHARS: usize = 64;
+// Defines the (zero-based) header line carrying the
+// `zakopane-algorithm: <token>` declaration.
+const ALGORITHM_LINE_INDEX: usize = 2;
+
+const ALGORITHM_LINE_PREFIX: &str = "zakopane-algorithm: ";
 
 // Defines a zakopane snapshot, which maps paths to checksums.
 #[derive(Debug)]
 pub struct Snapshot {
     contents: std::collections::HashMap<String, String>,
+    algorithm: ChecksumAlgorithm,
 }
 
 // Defines a valid zakopane snapshot header.
 const SNAPSHOT_HEADER_FOR_TESTING: &str = indoc::indoc!(
     r#"zakopane: <some datestamp>
        zakopane: /home/kalvin
-       # this line is typically empty but must be present
+       zakopane-algorithm: sha256
+
     "#
 );
 
@@ -35,41 +41,62 @@ pub fn snapshot_string_for_testing(checksums: &str) -> String {
 
 // Borrows the string representation of a line in a zakopane snapshot
 // and returns sliced str's in a tuple of (checksum, path).
-fn parse_snapshot_line(line: &str) -> Result<(&str, &str), ZakopaneError> {
+// `checksum_chars` is the hex width this snapshot's algorithm (as
+// declared in its header) produces.
+fn parse_snapshot_line(line: &str, checksum_chars: usize) -> Result<(&str, &str), ZakopaneError> {
     let bad_line = ZakopaneError::Snapshot(format!("malformed snapshot line: ``{line}''"));
     // A snapshot line should consist of the checksum, two spaces, and a
     // non-empty pathname.
-    if line.len() < CHECKSUM_CHARS + 3
-        || !line.is_char_boundary(CHECKSUM_CHARS)
-        || !line.is_char_boundary(CHECKSUM_CHARS + 1)
-        || !line.is_char_boundary(CHECKSUM_CHARS + 2)
+    if line.len() < checksum_chars + 3
+        || !line.is_char_boundary(checksum_chars)
+        || !line.is_char_boundary(checksum_chars + 1)
+        || !line.is_char_boundary(checksum_chars + 2)
     {
         return Err(bad_line);
     }
 
-    let (checksum, path_with_leading_space) = line.split_at(CHECKSUM_CHARS);
+    let (checksum, path_with_leading_space) = line.split_at(checksum_chars);
     if !path_with_leading_space.starts_with("  ") {
         return Err(bad_line);
     }
     Ok((checksum, &path_with_leading_space[2..]))
 }
 
+// Parses the `zakopane-algorithm: <token>` header line into a
+// ChecksumAlgorithm.
+fn parse_algorithm_line(line: &str) -> Result<ChecksumAlgorithm, ZakopaneError> {
+    let token = line.strip_prefix(ALGORITHM_LINE_PREFIX).ok_or_else(|| {
+        ZakopaneError::Snapshot(format!("malformed algorithm header line: ``{line}''"))
+    })?;
+    ChecksumAlgorithm::from_snapshot_token(token)
+        .ok_or_else(|| ZakopaneError::Snapshot(format!("unknown checksum algorithm: ``{token}''")))
+}
+
 impl Snapshot {
     // Borrows the string representation of a zakopane snapshot and
     // returns the corresponding Snapshot struct.
     pub fn new(snapshot: &str) -> Result<Snapshot, ZakopaneError> {
-        // A zakopane snapshot starts with three extra lines intended
-        // for human readers. zakopane doesn't care about this header.
+        // A zakopane snapshot starts with four extra lines intended
+        // for human readers, one of which (`ALGORITHM_LINE_INDEX`)
+        // declares the digest algorithm every checksum line below was
+        // computed with.
         let mut header_drain: usize = HEADER_LINES;
+        let mut algorithm: Option<ChecksumAlgorithm> = None;
 
         let mut contents = std::collections::HashMap::<String, String>::new();
-        for line in snapshot.lines() {
+        for (index, line) in snapshot.lines().enumerate() {
             if header_drain > 0 {
+                if index == ALGORITHM_LINE_INDEX {
+                    algorithm = Some(parse_algorithm_line(line)?);
+                }
                 header_drain -= 1;
                 continue;
             }
 
-            let (checksum, path) = parse_snapshot_line(line)?;
+            // `algorithm` is always Some() by the time the header is
+            // drained, since ALGORITHM_LINE_INDEX < HEADER_LINES.
+            let checksum_chars = algorithm.unwrap().hex_width();
+            let (checksum, path) = parse_snapshot_line(line, checksum_chars)?;
             if let Some(_old_checksum) = contents.insert(path.to_string(), checksum.to_string()) {
                 return Err(ZakopaneError::Snapshot(format!("path collision: {path}")));
             };
@@ -80,7 +107,16 @@ impl Snapshot {
                 "truncated zakopane snapshot".to_string(),
             ));
         }
-        Ok(Snapshot { contents: contents })
+        Ok(Snapshot {
+            contents,
+            algorithm: algorithm.unwrap(),
+        })
+    }
+
+    // Returns the digest algorithm this snapshot's checksums were
+    // computed with.
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
     }
 
     // Passes the inner struct's iterator straight out.
@@ -94,6 +130,20 @@ impl Snapshot {
     }
 }
 
+// Rejects a pair of snapshots taken with different digest algorithms;
+// comparing their checksums path-for-path would otherwise always
+// report every file as modified.
+pub fn ensure_same_algorithm(a: &Snapshot, b: &Snapshot) -> Result<(), ZakopaneError> {
+    if a.algorithm != b.algorithm {
+        return Err(ZakopaneError::Snapshot(format!(
+            "snapshots use different checksum algorithms: {} vs {}",
+            a.algorithm.snapshot_token(),
+            b.algorithm.snapshot_token()
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,4 +259,62 @@ zakopane: /home/kalvin
         // sequence of bytes.
         assert!(snapshot.get("a/bold-one.txt").is_none());
     }
+
+    #[test]
+    fn snapshot_parses_declared_algorithm() {
+        let snapshot = Snapshot::new(SNAPSHOT_HEADER_FOR_TESTING).unwrap();
+        assert_eq!(snapshot.algorithm(), ChecksumAlgorithm::Sha256);
+
+        let sha512_header = indoc::indoc!(
+            r#"zakopane: <some datestamp>
+               zakopane: /home/kalvin
+               zakopane-algorithm: sha512
+
+            "#
+        );
+        let checksum_128_hex = "0".repeat(128);
+        let snapshot = Snapshot::new(&format!(
+            "{sha512_header}{checksum_128_hex}  ./hello/there.txt\n"
+        ))
+        .unwrap();
+        assert_eq!(snapshot.algorithm(), ChecksumAlgorithm::Sha512);
+        assert_eq!(
+            snapshot.get("./hello/there.txt").unwrap(),
+            &checksum_128_hex
+        );
+    }
+
+    #[test]
+    fn snapshot_rejects_unknown_algorithm_token() {
+        let bad_header = indoc::indoc!(
+            r#"zakopane: <some datestamp>
+               zakopane: /home/kalvin
+               zakopane-algorithm: md5
+
+            "#
+        );
+        assert_snapshot_error(
+            Snapshot::new(bad_header).unwrap_err(),
+            "unknown checksum algorithm",
+        );
+    }
+
+    #[test]
+    fn ensure_same_algorithm_rejects_mismatched_snapshots() {
+        let sha256_snapshot = Snapshot::new(SNAPSHOT_HEADER_FOR_TESTING).unwrap();
+        let sha512_header = indoc::indoc!(
+            r#"zakopane: <some datestamp>
+               zakopane: /home/kalvin
+               zakopane-algorithm: sha512
+
+            "#
+        );
+        let sha512_snapshot = Snapshot::new(sha512_header).unwrap();
+
+        assert_snapshot_error(
+            ensure_same_algorithm(&sha256_snapshot, &sha512_snapshot).unwrap_err(),
+            "snapshots use different checksum algorithms",
+        );
+        assert!(ensure_same_algorithm(&sha256_snapshot, &sha256_snapshot).is_ok());
+    }
 }
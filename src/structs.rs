@@ -11,6 +11,10 @@ pub enum ZakopaneError {
     Snapshot(String),
     // Describes invalid command-line invocations.
     CommandLine(String),
+    // Collects several errors encountered while processing something
+    // that shouldn't bail on the first one (e.g. a config file with
+    // several malformed policy entries).
+    Multiple(Vec<ZakopaneError>),
 }
 
 impl std::fmt::Display for ZakopaneError {
@@ -20,10 +24,125 @@ impl std::fmt::Display for ZakopaneError {
             ZakopaneError::Config(message)
             | ZakopaneError::Snapshot(message)
             | ZakopaneError::CommandLine(message) => write!(f, "{}", message),
+            ZakopaneError::Multiple(errors) => {
+                let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+                write!(f, "{}", messages.join("\n"))
+            }
         }
     }
 }
 
+#[derive(clap::Parser, Debug)]
+#[command(name = "zakopane", about = "checksums and compares directory trees")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub subcommand: Subcommand,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Subcommand {
+    /// Computes and writes a checksum snapshot for a directory tree.
+    Checksum(ChecksumArgs),
+    /// Compares two checksum snapshots and reports policy violations.
+    Compare(CompareArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ChecksumArgs {
+    /// Directory tree to checksum.
+    pub target: std::path::PathBuf,
+    /// Where to write the resulting snapshot.
+    #[arg(long)]
+    pub output_path: std::path::PathBuf,
+    #[arg(long, default_value_t = 4)]
+    pub max_tasks: usize,
+    #[arg(long)]
+    pub big_file_bytes: Option<u64>,
+    /// Selects the digest algorithm used to checksum each file. The
+    /// chosen algorithm is recorded in the snapshot's header so a
+    /// later `compare` can refuse to mix snapshots taken with
+    /// different algorithms.
+    #[arg(long, value_enum, default_value_t = ChecksumAlgorithm::Sha256)]
+    pub hash: ChecksumAlgorithm,
+}
+
+// Selects which digest algorithm `checksum` uses to hash each file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl ChecksumAlgorithm {
+    // The token written into (and read back from) a snapshot's
+    // `zakopane-algorithm` header line.
+    pub fn snapshot_token(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256",
+            ChecksumAlgorithm::Sha512 => "sha512",
+            ChecksumAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    // Parses a snapshot's `zakopane-algorithm` header token back into
+    // a ChecksumAlgorithm, or None if the token isn't recognized.
+    pub fn from_snapshot_token(token: &str) -> Option<ChecksumAlgorithm> {
+        match token {
+            "sha256" => Some(ChecksumAlgorithm::Sha256),
+            "sha512" => Some(ChecksumAlgorithm::Sha512),
+            "blake3" => Some(ChecksumAlgorithm::Blake3),
+            _ => None,
+        }
+    }
+
+    // The width, in hex characters, of a digest produced by this
+    // algorithm. sha256 and blake3 both produce 32-byte digests;
+    // sha512 produces 64 bytes.
+    pub fn hex_width(&self) -> usize {
+        match self {
+            ChecksumAlgorithm::Sha256 | ChecksumAlgorithm::Blake3 => 64,
+            ChecksumAlgorithm::Sha512 => 128,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CompareArgs {
+    /// Path to a zakopane config file. May be repeated; later files'
+    /// policies are appended and their default-policy (if set)
+    /// overrides earlier ones. A config's own `include` key merges in
+    /// the same way.
+    #[arg(long)]
+    pub config: Vec<std::path::PathBuf>,
+    /// The older of the two snapshots being compared.
+    pub old_snapshot: std::path::PathBuf,
+    /// The newer of the two snapshots being compared.
+    pub new_snapshot: std::path::PathBuf,
+    /// Selects how violations are printed to stdout.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Also emit each violation as a problem-matcher-compatible line
+    /// (`severity: path:1:1: message`), for CI/editor annotation.
+    #[arg(long)]
+    pub problem_matcher: bool,
+    /// Exits non-zero if the worst Error-severity violation reaches at
+    /// least this kind. Omit to always exit zero regardless of what
+    /// was found.
+    #[arg(long, value_enum)]
+    pub fail_on: Option<crate::violations::ViolationType>,
+}
+
+// Selects the serialization `do_compare` uses to print a `Violations`
+// report.
+#[derive(Clone, Debug, clap::ValueEnum)]
+pub enum OutputFormat {
+    // Today's `+ ! -` per-path listing.
+    Text,
+    // A JSON object of violations plus a per-kind summary count.
+    Json,
+}
+
 #[derive(Debug)]
 pub struct ChecksumCliOptions {
     pub path: std::path::PathBuf,
@@ -33,8 +152,11 @@ pub struct ChecksumCliOptions {
 
     // User-defined value for what constitutes a "big file" for which
     // the checksum dispatcher will force single-threaded digest
-    // calculation.
+    // calculation. Orthogonal to `algorithm`: it governs scheduling,
+    // not which digest gets computed.
     pub big_file_bytes: Option<u64>,
+
+    pub algorithm: ChecksumAlgorithm,
 }
 
 impl ChecksumCliOptions {
@@ -43,6 +165,7 @@ impl ChecksumCliOptions {
         optional_output_path: Option<std::path::PathBuf>,
         max_tasks: usize,
         big_file_bytes: Option<u64>,
+        algorithm: ChecksumAlgorithm,
     ) -> Result<Self, ZakopaneError> {
         if max_tasks < 1 {
             return Err(ZakopaneError::CommandLine(format!(
@@ -63,6 +186,7 @@ impl ChecksumCliOptions {
             start_time,
             max_tasks,
             big_file_bytes,
+            algorithm,
         })
     }
 }
@@ -2,8 +2,8 @@
 // violations - i.e. human-readable descriptions of notable
 // discrepancies between zakopane snapshots.
 
-#[derive(Clone, Eq, Ord, PartialEq, PartialOrd)]
-enum ViolationType {
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, clap::ValueEnum)]
+pub enum ViolationType {
     Added,
     Deleted,
     Modified,
@@ -20,8 +20,71 @@ impl std::fmt::Display for ViolationType {
     }
 }
 
+impl ViolationType {
+    // Returns the `kind` string used in structured (JSON, problem
+    // matcher) output.
+    fn json_kind(&self) -> &'static str {
+        match self {
+            ViolationType::Added => "added",
+            ViolationType::Deleted => "deleted",
+            ViolationType::Modified => "modified",
+        }
+    }
+}
+
+// How seriously a single violation should be taken. A path whose
+// matched policy is `ignore` is merely noted (`Info`); a path whose
+// matched policy actively forbids what happened (e.g. `nomodify` and
+// the file changed) is an `Error`. Declaration order is significant:
+// deriving `Ord` this way lets `Violations::worst_severity` and
+// friends treat `Error` as strictly worse than `Info`.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    Info,
+    Error,
+}
+
+impl Severity {
+    // The token used in JSON and problem-matcher output.
+    fn json_kind(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Error => "error",
+        }
+    }
+}
+
+// Escapes `s` for inclusion in a JSON string literal.
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// One recorded violation. `checksums` is only ever populated for
+// `Modified` entries, carrying the (old, new) hex digests so a
+// `Modified` report can show what actually changed rather than just
+// that something did.
+#[derive(Clone, Eq, Ord, PartialEq, PartialOrd)]
+struct Entry {
+    path: String,
+    kind: ViolationType,
+    severity: Severity,
+    checksums: Option<(String, String)>,
+}
+
 pub struct Violations {
-    data: std::vec::Vec<(String, ViolationType)>,
+    data: std::vec::Vec<Entry>,
 }
 
 impl Violations {
@@ -29,14 +92,132 @@ impl Violations {
         Violations { data: vec![] }
     }
 
-    pub fn added(&mut self, path: &str) {
-        self.data.push((path.to_owned(), ViolationType::Added));
+    pub fn added(&mut self, path: &str, severity: Severity) {
+        self.data.push(Entry {
+            path: path.to_owned(),
+            kind: ViolationType::Added,
+            severity,
+            checksums: None,
+        });
     }
-    pub fn deleted(&mut self, path: &str) {
-        self.data.push((path.to_owned(), ViolationType::Deleted));
+    pub fn deleted(&mut self, path: &str, severity: Severity) {
+        self.data.push(Entry {
+            path: path.to_owned(),
+            kind: ViolationType::Deleted,
+            severity,
+            checksums: None,
+        });
     }
-    pub fn modified(&mut self, path: &str) {
-        self.data.push((path.to_owned(), ViolationType::Modified));
+    pub fn modified(
+        &mut self,
+        path: &str,
+        severity: Severity,
+        old_checksum: &str,
+        new_checksum: &str,
+    ) {
+        self.data.push(Entry {
+            path: path.to_owned(),
+            kind: ViolationType::Modified,
+            severity,
+            checksums: Some((old_checksum.to_owned(), new_checksum.to_owned())),
+        });
+    }
+
+    // Returns the worst (highest) severity among this Violations's
+    // entries, or None if there are no violations at all.
+    pub fn worst_severity(&self) -> Option<Severity> {
+        self.data.iter().map(|entry| entry.severity).max()
+    }
+
+    // Returns the highest-ranked violation kind among this Violations's
+    // Error-severity entries. Info-severity entries (e.g. a change to
+    // an ignored path) don't count towards a `--fail-on` threshold,
+    // since they were never actual policy breaches.
+    pub fn worst_error_kind(&self) -> Option<ViolationType> {
+        self.data
+            .iter()
+            .filter(|entry| entry.severity == Severity::Error)
+            .map(|entry| entry.kind)
+            .max()
+    }
+
+    // Serializes this Violations as a JSON object suitable for machine
+    // consumption: a `violations` array of
+    // `{"path": ..., "kind": "added|deleted|modified", "severity":
+    // "info|error"}` records (`Modified` records additionally carry
+    // `"old_checksum"`/`"new_checksum"`) plus a `summary` object
+    // counting each kind.
+    pub fn to_json(&self) -> String {
+        let mut sorted_violations = self.data.to_owned();
+        sorted_violations.sort_unstable();
+
+        let mut added = 0;
+        let mut deleted = 0;
+        let mut modified = 0;
+        let records: Vec<String> = sorted_violations
+            .iter()
+            .map(|entry| {
+                match entry.kind {
+                    ViolationType::Added => added += 1,
+                    ViolationType::Deleted => deleted += 1,
+                    ViolationType::Modified => modified += 1,
+                };
+                let checksums = match &entry.checksums {
+                    Some((old_checksum, new_checksum)) => format!(
+                        r#", "old_checksum": "{}", "new_checksum": "{}""#,
+                        json_escape(old_checksum),
+                        json_escape(new_checksum),
+                    ),
+                    None => String::new(),
+                };
+                format!(
+                    r#"{{"path": "{}", "kind": "{}", "severity": "{}"{}}}"#,
+                    json_escape(&entry.path),
+                    entry.kind.json_kind(),
+                    entry.severity.json_kind(),
+                    checksums,
+                )
+            })
+            .collect();
+
+        format!(
+            r#"{{"violations": [{}], "summary": {{"added": {}, "deleted": {}, "modified": {}}}}}"#,
+            records.join(", "),
+            added,
+            deleted,
+            modified,
+        )
+    }
+
+    // Serializes this Violations as a stream of problem-matcher-style
+    // lines (`severity: path:1:1: message`), one per violation, so CI
+    // systems and editors can annotate the offending paths directly
+    // instead of scraping the `Display` text.
+    pub fn to_problem_matcher(&self) -> String {
+        let mut sorted_violations = self.data.to_owned();
+        sorted_violations.sort_unstable();
+
+        let mut buffer = String::new();
+        for entry in sorted_violations.iter() {
+            buffer.push_str(&format!(
+                "{}: {}:1:1: {} was {}{}\n",
+                entry.severity.json_kind(),
+                entry.path,
+                entry.path,
+                entry.kind.json_kind(),
+                checksum_suffix(&entry.checksums),
+            ));
+        }
+        buffer
+    }
+}
+
+// Formats a `Modified` entry's (old, new) checksums as `" (old ->
+// new)"`, or an empty string for entries that don't carry checksums.
+fn checksum_suffix(checksums: &Option<(String, String)>) -> String {
+    match checksums {
+        Some((old_checksum, new_checksum)) => format!(" ({} -> {})", old_checksum, new_checksum),
+        None => String::new(),
     }
 }
 
@@ -44,8 +225,14 @@ impl std::fmt::Display for Violations {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut sorted_violations = self.data.to_owned();
         sorted_violations.sort_unstable();
-        for (path, kind) in sorted_violations.into_iter() {
-            write!(f, "{kind} {path}\n")?;
+        for entry in sorted_violations.into_iter() {
+            write!(
+                f,
+                "{} {}{}\n",
+                entry.kind,
+                entry.path,
+                checksum_suffix(&entry.checksums)
+            )?;
         }
         Ok(())
     }
@@ -59,25 +246,79 @@ mod tests {
     #[test]
     fn violations_display() {
         let mut violations = Violations::new();
-        violations.added("jello there!");
-        violations.modified("iello there!");
-        violations.deleted("hello there!");
-        violations.added("a/path/of/some/sort");
-        violations.modified("b/path/of/some/sort");
-        violations.deleted("z/path/of/some/sort");
+        violations.added("jello there!", Severity::Error);
+        violations.modified("iello there!", Severity::Error, "aaa", "bbb");
+        violations.deleted("hello there!", Severity::Error);
+        violations.added("a/path/of/some/sort", Severity::Error);
+        violations.modified("b/path/of/some/sort", Severity::Error, "ccc", "ddd");
+        violations.deleted("z/path/of/some/sort", Severity::Error);
 
         assert_eq!(
             format!("{}", violations),
             indoc!(
                 r#"
                + a/path/of/some/sort
-               ! b/path/of/some/sort
+               ! b/path/of/some/sort (ccc -> ddd)
                - hello there!
-               ! iello there!
+               ! iello there! (aaa -> bbb)
                + jello there!
                - z/path/of/some/sort
             "#
             )
         );
     }
+
+    #[test]
+    fn violations_worst_severity() {
+        let mut violations = Violations::new();
+        assert_eq!(violations.worst_severity(), None);
+
+        violations.added("ignored/path", Severity::Info);
+        assert_eq!(violations.worst_severity(), Some(Severity::Info));
+
+        violations.modified("enforced/path", Severity::Error, "aaa", "bbb");
+        assert_eq!(violations.worst_severity(), Some(Severity::Error));
+    }
+
+    #[test]
+    fn violations_worst_error_kind_ignores_info_severity() {
+        let mut violations = Violations::new();
+        violations.modified("ignored/path", Severity::Info, "aaa", "bbb");
+        assert_eq!(violations.worst_error_kind(), None);
+
+        violations.added("enforced/path", Severity::Error);
+        assert_eq!(violations.worst_error_kind(), Some(ViolationType::Added));
+    }
+
+    #[test]
+    fn violations_to_json() {
+        let mut violations = Violations::new();
+        violations.added("a/path", Severity::Info);
+        violations.modified("b/path", Severity::Error, "aaa", "bbb");
+        violations.deleted(r#"c/"quoted"/path"#, Severity::Error);
+
+        assert_eq!(
+            violations.to_json(),
+            r#"{"violations": [{"path": "a/path", "kind": "added", "severity": "info"}, {"path": "b/path", "kind": "modified", "severity": "error", "old_checksum": "aaa", "new_checksum": "bbb"}, {"path": "c/\"quoted\"/path", "kind": "deleted", "severity": "error"}], "summary": {"added": 1, "deleted": 1, "modified": 1}}"#
+        );
+    }
+
+    #[test]
+    fn violations_to_problem_matcher() {
+        let mut violations = Violations::new();
+        violations.added("a/path", Severity::Info);
+        violations.modified("b/path", Severity::Error, "aaa", "bbb");
+        violations.deleted("c/path", Severity::Error);
+
+        assert_eq!(
+            violations.to_problem_matcher(),
+            indoc!(
+                r#"
+                info: a/path:1:1: a/path was added
+                error: b/path:1:1: b/path was modified (aaa -> bbb)
+                error: c/path:1:1: c/path was deleted
+                "#
+            )
+        );
+    }
 }
@@ -10,13 +10,14 @@ use libzakopane::snapshot::Snapshot;
 
 #[test]
 fn test_basic_default_immutability() {
-    let options = libzakopane::config::test_support::options(None, Some("immutable"));
-    let config: Config = Config::new(&options).unwrap();
+    // No config file at all means the baseline default-policy
+    // (immutable) applies.
+    let config: Config = Config::new(vec![]).unwrap();
 
     // Verifies that empty snapshots never turn up violations.
     let empty_older = Snapshot::new(&snapshot_string_for_testing("")).unwrap();
     let empty_newer = Snapshot::new(&snapshot_string_for_testing("")).unwrap();
-    let empty_violations = libzakopane::enter(&config, &empty_older, &empty_newer);
+    let empty_violations = libzakopane::compare(&config, &empty_older, &empty_newer);
     assert_eq!(empty_violations.to_string(), "");
 
     // Verifies that disjoint snapshots also violate this policy.
@@ -28,7 +29,7 @@ fn test_basic_default_immutability() {
         "0000000000000000000000000000000000000000000000000000000000000000  ./x/y/z",
     ))
     .unwrap();
-    let disjoint_violations = libzakopane::enter(&config, &disjoint_older, &disjoint_newer);
+    let disjoint_violations = libzakopane::compare(&config, &disjoint_older, &disjoint_newer);
     // From zakopane's point of view, ``./a/b/c'' was deleted and
     // ``./x/y/z'' was added.
     assert_eq!(
@@ -65,13 +66,13 @@ fn test_basic_default_immutability() {
         "#
     )))
     .unwrap();
-    let shifty_violations = libzakopane::enter(&config, &shifty_older, &shifty_newer);
+    let shifty_violations = libzakopane::compare(&config, &shifty_older, &shifty_newer);
     assert_eq!(
         shifty_violations.to_string(),
         indoc!(
             r#"
-            ! ./a/b/changed
-            ! ./i/j/changed
+            ! ./a/b/changed (0000000000000000000000000000000000000000000000000000000000000000 -> ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff)
+            ! ./i/j/changed (0000000000000000000000000000000000000000000000000000000000000000 -> ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff)
             "#
         )
     );
@@ -90,7 +91,7 @@ fn test_basic_default_immutability() {
     )))
     .unwrap();
     let the_same_shifty_violations =
-        libzakopane::enter(&config, &shifty_older, &shifty_newer_shuffled);
+        libzakopane::compare(&config, &shifty_older, &shifty_newer_shuffled);
     assert_eq!(
         shifty_violations.to_string(),
         the_same_shifty_violations.to_string()
@@ -101,9 +102,7 @@ fn test_basic_default_immutability() {
 fn test_overlapping_prefixes() {
     let config_path =
         libzakopane::config::test_support::data_path("config-with-several-more-policies");
-    let options =
-        libzakopane::config::test_support::options(Some(config_path.to_str().unwrap()), None);
-    let config = Config::new(&options).unwrap();
+    let config = Config::new(vec![config_path]).unwrap();
 
     let snapshot_older = Snapshot::new(&snapshot_string_for_testing(indoc!(
         r#"
@@ -133,17 +132,17 @@ fn test_overlapping_prefixes() {
     )))
     .unwrap();
 
-    let violations = libzakopane::enter(&config, &snapshot_older, &snapshot_newer);
+    let violations = libzakopane::compare(&config, &snapshot_older, &snapshot_newer);
     assert_eq!(
         violations.to_string(),
         indoc!(
             r#"
             - ./Documents/nodelete-1.txt
             + ./Music/copy-of-hello-there.mp3
-            ! ./Music/hello-there.mp3
-            ! ./Pictures/2019/something-supposedly-immutable.jpg
+            ! ./Music/hello-there.mp3 (0000000000000000000000000000000000000000000000000000000000000000 -> ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff)
+            ! ./Pictures/2019/something-supposedly-immutable.jpg (0000000000000000000000000000000000000000000000000000000000000000 -> ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff)
             + ./Pictures/copy-of-general-kenobi.gif
-            ! ./Pictures/general-kenobi.gif
+            ! ./Pictures/general-kenobi.gif (0000000000000000000000000000000000000000000000000000000000000000 -> ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff)
             "#
         )
     );
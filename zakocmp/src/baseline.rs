@@ -0,0 +1,85 @@
+// This module implements the accepted-violations baseline that backs
+// zakocmp's `--baseline`/`--write-baseline` workflow: a violation that
+// exactly matches a baseline entry is a previously-acknowledged
+// discrepancy and is suppressed from the "new" report, much like a
+// reviewer promoting `insta`'s `.snap.new` files to `.snap`.
+
+use std::collections::HashSet;
+
+use crate::structs::ZakocmpError;
+use crate::violations::violation_type_from_repr;
+
+// Records the set of (path, kind) violations a reviewer has already
+// signed off on.
+pub struct Baseline {
+    entries: HashSet<(String, i32)>,
+}
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        Baseline {
+            entries: HashSet::new(),
+        }
+    }
+
+    // Parses a baseline file. The format is identical to what
+    // `Violations`'s `Display` impl emits (e.g. `+ some/path`), so a
+    // baseline file is just a saved violations report.
+    pub fn parse(contents: &str) -> Result<Baseline, ZakocmpError> {
+        let mut entries = HashSet::new();
+        for line in contents.lines() {
+            if line.is_empty() {
+                continue;
+            }
+            let bad_line =
+                || ZakocmpError::Baseline(format!("malformed baseline line: ``{}''", line));
+            // The repr (`+`, `-`, `!`, `->`) is variable-width, so find
+            // the space that separates it from the path rather than
+            // assuming a fixed-width prefix.
+            let space_index = line.find(' ').ok_or_else(bad_line)?;
+            let (kind_repr, rest) = line.split_at(space_index);
+            let kind = violation_type_from_repr(kind_repr).ok_or_else(bad_line)?;
+            entries.insert((rest[1..].to_string(), kind));
+        }
+        Ok(Baseline { entries })
+    }
+
+    // Returns whether (path, kind) has already been acknowledged.
+    pub fn contains(&self, path: &str, kind: i32) -> bool {
+        self.entries.contains(&(path.to_string(), kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::violations::{ADDED, DELETED, MODIFIED};
+
+    #[test]
+    fn baseline_parses_violations_display_output() {
+        let baseline = Baseline::parse(
+            r#"+ a/path/of/some/sort
+! b/path/of/some/sort
+- hello there!
+"#,
+        )
+        .unwrap();
+        assert!(baseline.contains("a/path/of/some/sort", ADDED));
+        assert!(baseline.contains("b/path/of/some/sort", MODIFIED));
+        assert!(baseline.contains("hello there!", DELETED));
+        assert!(!baseline.contains("hello there!", ADDED));
+        assert!(!baseline.contains("untracked/path", MODIFIED));
+    }
+
+    #[test]
+    fn baseline_rejects_malformed_lines() {
+        assert!(Baseline::parse("?  not a real kind\n").is_err());
+        assert!(Baseline::parse("+no-separating-space\n").is_err());
+    }
+
+    #[test]
+    fn baseline_can_be_empty() {
+        let baseline = Baseline::parse("").unwrap();
+        assert!(!baseline.contains("anything", ADDED));
+    }
+}
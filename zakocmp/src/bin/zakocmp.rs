@@ -1,6 +1,8 @@
+use libzakocmp::baseline::Baseline;
 use libzakocmp::config::Config;
 use libzakocmp::snapshot::Snapshot;
 use libzakocmp::structs::CliOptions;
+use libzakocmp::structs::OutputFormat;
 use libzakocmp::structs::ZakocmpError;
 
 use clap::{App, Arg, ArgMatches};
@@ -9,12 +11,56 @@ const DEFAULT_POLICY_ARG_NAME: &'static str = "default-policy";
 const CONFIG_FILE_ARG_NAME: &'static str = "config";
 const OLD_SNAPSHOT_PATH_ARG_NAME: &'static str = "old-snapshot-path";
 const NEW_SNAPSHOT_PATH_ARG_NAME: &'static str = "new-snapshot-path";
+const BASELINE_ARG_NAME: &'static str = "baseline";
+const WRITE_BASELINE_ARG_NAME: &'static str = "write-baseline";
+const FORMAT_ARG_NAME: &'static str = "format";
+const REVIEW_ARG_NAME: &'static str = "review";
+const REVIEW_COMMAND_ARG_NAME: &'static str = "review-command";
+const QUIET_ARG_NAME: &'static str = "quiet";
+
+// Exit codes. Distinct from each other so scripts can tell "zakocmp
+// itself failed" (bad arguments, unreadable files, ...) apart from
+// "zakocmp ran fine and found violations".
+const EXIT_ERROR: i32 = 1;
+const EXIT_VIOLATIONS: i32 = 2;
 
 // Holds one instance of each struct necessary to operate.
 struct OperationalData {
     config: Config,
     old_snapshot: Snapshot,
     new_snapshot: Snapshot,
+    baseline_path: Option<String>,
+    write_baseline: bool,
+    output_format: OutputFormat,
+    review: bool,
+    review_command: String,
+    quiet: bool,
+}
+
+// Detects whether zakocmp is running unattended in CI, mirroring
+// insta's `is_ci` helper: true whenever the conventional `CI`
+// environment variable is set to anything other than an empty value
+// or an explicit "false"/"0".
+fn is_ci() -> bool {
+    match std::env::var("CI") {
+        Ok(value) => !matches!(value.to_lowercase().as_str(), "" | "0" | "false"),
+        Err(_) => false,
+    }
+}
+
+// Opens a snapshot argument, treating `-` as stdin so a snapshot can
+// be piped in (e.g. `zakocmp_snapshot_generator | zakocmp ...`)
+// instead of always coming from a file.
+fn open_snapshot(path: &str) -> Result<Snapshot, ZakocmpError> {
+    if path == "-" {
+        return Snapshot::new(&libzakocmp::helpers::ingest_reader(std::io::stdin())?);
+    }
+    Snapshot::from_reader(libzakocmp::helpers::open_file(path)?).map_err(|e| match e {
+        ZakocmpError::Io(io_error) => {
+            ZakocmpError::IoWithPath(std::path::PathBuf::from(path), io_error)
+        }
+        other => other,
+    })
 }
 
 // Reads parsed command-line arguments and returns the appropriate
@@ -23,18 +69,42 @@ fn complete_initialization(matches: &ArgMatches) -> Result<OperationalData, Zako
     // The two snapshot paths are required, so these are safe to unwrap.
     let old_snapshot_path = matches.value_of(OLD_SNAPSHOT_PATH_ARG_NAME).unwrap();
     let new_snapshot_path = matches.value_of(NEW_SNAPSHOT_PATH_ARG_NAME).unwrap();
-    let old_contents = libzakocmp::helpers::ingest_file(old_snapshot_path)?;
-    let new_contents = libzakocmp::helpers::ingest_file(new_snapshot_path)?;
+    // Unattended CI runs default to minimal output (unless the caller
+    // asked for something specific via --format).
+    let default_format = if is_ci() { "minimal" } else { "full" };
+    let output_format: OutputFormat = matches
+        .value_of(FORMAT_ARG_NAME)
+        .unwrap_or(default_format)
+        .parse()?;
 
     let options = CliOptions {
+        old_snapshot_path: old_snapshot_path,
+        new_snapshot_path: new_snapshot_path,
         config_path: matches.value_of(CONFIG_FILE_ARG_NAME),
         default_policy: matches.value_of(DEFAULT_POLICY_ARG_NAME),
+        baseline_path: matches.value_of(BASELINE_ARG_NAME),
+        write_baseline: matches.is_present(WRITE_BASELINE_ARG_NAME),
+        output_format: output_format,
+        review: matches.is_present(REVIEW_ARG_NAME),
+        review_command: matches.value_of(REVIEW_COMMAND_ARG_NAME),
+        quiet: matches.is_present(QUIET_ARG_NAME),
     };
 
+    let review_command = options
+        .review_command
+        .map(str::to_owned)
+        .unwrap_or_else(libzakocmp::review::default_review_command);
+
     Ok(OperationalData {
         config: Config::new(&options)?,
-        old_snapshot: Snapshot::new(&old_contents)?,
-        new_snapshot: Snapshot::new(&new_contents)?,
+        old_snapshot: open_snapshot(old_snapshot_path)?,
+        new_snapshot: open_snapshot(new_snapshot_path)?,
+        baseline_path: options.baseline_path.map(str::to_owned),
+        write_baseline: options.write_baseline,
+        output_format: options.output_format,
+        review: options.review,
+        review_command: review_command,
+        quiet: options.quiet,
     })
 }
 
@@ -63,26 +133,134 @@ fn initialize() -> Result<OperationalData, ZakocmpError> {
         )
         .arg(
             Arg::with_name(OLD_SNAPSHOT_PATH_ARG_NAME)
-                .help("path to older snapshot")
+                .help("path to older snapshot, or - to read from stdin")
                 .index(1)
                 .required(true),
         )
         .arg(
             Arg::with_name(NEW_SNAPSHOT_PATH_ARG_NAME)
-                .help("path to newer snapshot")
+                .help("path to newer snapshot, or - to read from stdin")
                 .index(2)
                 .required(true),
         )
+        .arg(
+            Arg::with_name(BASELINE_ARG_NAME)
+                .long("baseline")
+                .value_name("FILE")
+                .help("path to a baseline of previously-acknowledged violations")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(WRITE_BASELINE_ARG_NAME)
+                .long("write-baseline")
+                .help("writes this run's violations to --baseline instead of comparing against it")
+                .requires(BASELINE_ARG_NAME),
+        )
+        .arg(
+            Arg::with_name(FORMAT_ARG_NAME)
+                .long("format")
+                .value_name("full|summary|minimal|json")
+                .help("selects how violations are printed")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(REVIEW_ARG_NAME)
+                .long("review")
+                .help("interactively walks new violations, accepting or skipping each into the baseline")
+                .requires(BASELINE_ARG_NAME),
+        )
+        .arg(
+            Arg::with_name(REVIEW_COMMAND_ARG_NAME)
+                .long("review-command")
+                .value_name("CMD")
+                .help("command used to show a flagged path during review (default: $PAGER, else less)")
+                .takes_value(true)
+                .requires(REVIEW_ARG_NAME),
+        )
+        .arg(
+            Arg::with_name(QUIET_ARG_NAME)
+                .long("quiet")
+                .alias("check")
+                .help("prints nothing; the exit code alone reports whether violations were found")
+                .conflicts_with(REVIEW_ARG_NAME),
+        )
         .get_matches();
     return complete_initialization(&matches);
 }
 
+// Loads the baseline at `path` (if any). A run with no `--baseline`
+// flag behaves as though the baseline were empty: nothing is
+// acknowledged.
+fn load_baseline(path: &Option<String>) -> Result<Baseline, ZakocmpError> {
+    match path {
+        Some(path) => match libzakocmp::helpers::ingest_file(path) {
+            Ok(contents) => Baseline::parse(&contents),
+            // A missing baseline file just means nothing has been
+            // acknowledged yet; any other I/O error still propagates.
+            Err(ZakocmpError::IoWithPath(_, ref io_error))
+                if io_error.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Ok(Baseline::new())
+            }
+            Err(e) => Err(e),
+        },
+        None => Ok(Baseline::new()),
+    }
+}
+
+// Walks `violations` one at a time in an interactive review: each
+// flagged path is shown via `review_command`, and the operator decides
+// whether to accept it into the baseline or leave it outstanding.
+// Returns the accepted and still-outstanding entries, each formatted
+// exactly as the baseline file (and `Violations`'s `Display` impl)
+// expects.
+fn run_review(
+    violations: &libzakocmp::violations::Violations,
+    review_command: &str,
+) -> Result<(Vec<String>, Vec<String>), ZakocmpError> {
+    let mut accepted = Vec::new();
+    let mut remaining = Vec::new();
+    for (path, kind) in violations.iter() {
+        let line = libzakocmp::violations::format_entry(&path, kind);
+        println!("{}", line);
+        libzakocmp::review::show_path(review_command, &path)?;
+        print!("accept into baseline? [y/N] ");
+        std::io::Write::flush(&mut std::io::stdout()).ok();
+
+        let mut answer = String::new();
+        std::io::stdin()
+            .read_line(&mut answer)
+            .map_err(ZakocmpError::Io)?;
+        if answer.trim().eq_ignore_ascii_case("y") {
+            accepted.push(line);
+        } else {
+            remaining.push(line);
+        }
+    }
+    Ok((accepted, remaining))
+}
+
+// Appends `lines` to the baseline file at `baseline_path`, preserving
+// whatever is already recorded there. Mirrors `--write-baseline`'s
+// text format: one `Display`-style line per violation.
+fn append_to_baseline(baseline_path: &str, lines: &Vec<String>) -> Result<(), ZakocmpError> {
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(baseline_path)
+        .map_err(|e| ZakocmpError::IoWithPath(std::path::PathBuf::from(baseline_path), e))?;
+    std::io::Write::write_all(&mut file, contents.as_bytes())
+        .map_err(|e| ZakocmpError::IoWithPath(std::path::PathBuf::from(baseline_path), e))
+}
+
 fn main() {
     let operational_data = match initialize() {
         Ok(data) => data,
         Err(error) => {
             eprintln!("{}", error.to_string());
-            std::process::exit(1);
+            std::process::exit(EXIT_ERROR);
         }
     };
 
@@ -90,8 +268,93 @@ fn main() {
         config,
         new_snapshot,
         old_snapshot,
+        baseline_path,
+        write_baseline,
+        output_format,
+        review,
+        review_command,
+        quiet,
     } = operational_data;
     assert!(config.rules() > 0);
-    let violations = libzakocmp::enter(&config, &old_snapshot, &new_snapshot);
-    println!("{}", violations);
+    let violations = match libzakocmp::enter(&config, &old_snapshot, &new_snapshot) {
+        Ok(violations) => violations,
+        Err(error) => {
+            eprintln!("{}", error.to_string());
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+
+    if write_baseline {
+        let baseline_path = baseline_path.expect("--write-baseline requires --baseline");
+        std::fs::write(&baseline_path, violations.to_string()).unwrap_or_else(|e| {
+            eprintln!("failed to write baseline ``{}'': {}", baseline_path, e);
+            std::process::exit(EXIT_ERROR);
+        });
+        if !quiet {
+            println!("wrote baseline ``{}''", baseline_path);
+        }
+        return;
+    }
+
+    let baseline = match load_baseline(&baseline_path) {
+        Ok(baseline) => baseline,
+        Err(error) => {
+            eprintln!("{}", error.to_string());
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+    let (new_violations, acknowledged) = violations.partition_against_baseline(&baseline);
+
+    if review {
+        let baseline_path = baseline_path.expect("--review requires --baseline");
+        let (accepted, remaining) = match run_review(&new_violations, &review_command) {
+            Ok(pair) => pair,
+            Err(error) => {
+                eprintln!("{}", error.to_string());
+                std::process::exit(EXIT_ERROR);
+            }
+        };
+        if !accepted.is_empty() {
+            if let Err(error) = append_to_baseline(&baseline_path, &accepted) {
+                eprintln!("{}", error.to_string());
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+        for line in &remaining {
+            println!("{}", line);
+        }
+        if !remaining.is_empty() {
+            std::process::exit(EXIT_VIOLATIONS);
+        }
+        return;
+    }
+
+    if !quiet {
+        match output_format {
+            OutputFormat::Json => println!("{}", new_violations.to_json()),
+            OutputFormat::Full => {
+                if !acknowledged.is_empty() {
+                    println!("acknowledged:");
+                    print!("{}", acknowledged);
+                }
+                print!("{}", new_violations);
+            }
+            OutputFormat::Summary => {
+                let counts = new_violations.counts();
+                println!(
+                    "added: {}, deleted: {}, modified: {}, moved: {}",
+                    counts.added, counts.deleted, counts.modified, counts.moved
+                );
+            }
+            OutputFormat::Minimal => {
+                let total = new_violations.counts().total();
+                if total > 0 {
+                    println!("{} violation{}", total, if total == 1 { "" } else { "s" });
+                }
+            }
+        }
+    }
+    if !new_violations.is_empty() {
+        std::process::exit(EXIT_VIOLATIONS);
+    }
 }
@@ -2,23 +2,32 @@
 // configuration files.
 
 use std::clone::Clone;
+use std::collections::HashMap;
 use std::error::Error;
 
 use yaml_rust::{Yaml, YamlLoader};
 
+use crate::matcher::Matcher;
 use crate::structs::CliOptions;
 use crate::structs::ZakocmpError;
 
 // Represents a single zakopane config policy.
 type Policy = i32;
 
-// Represents a sorted vector of zakopane config rules, each mapping a
-// path (prefix) to a policy. This type alias is provided for ease of
+// Maps a config's `definitions` alias names to their (still
+// unresolved) token expressions, e.g. `{"archive": "noadd,nodelete"}`.
+type Definitions = HashMap<String, String>;
+
+// Represents a vector of zakopane config rules, each mapping a
+// compiled policy key (a literal prefix, a glob pattern, or an
+// anchored regex) to a policy. This type alias is provided for ease of
 // coding.
-type Policies = Vec<(String, Policy)>;
+type Policies = Vec<(Matcher, Policy)>;
 
 const DEFAULT_POLICY_KEY: &'static str = "default-policy";
 const POLICIES_KEY: &'static str = "policies";
+const INCLUDE_KEY: &'static str = "include";
+const DEFINITIONS_KEY: &'static str = "definitions";
 
 // Enumerates the string representations of known policies.
 const POLICY_REPR_IGNORE: &'static str = "ignore";
@@ -26,6 +35,7 @@ const POLICY_REPR_NOADD: &'static str = "noadd";
 const POLICY_REPR_NODELETE: &'static str = "nodelete";
 const POLICY_REPR_NOMODIFY: &'static str = "nomodify";
 const POLICY_REPR_IMMUTABLE: &'static str = "immutable";
+const POLICY_REPR_NOMOVE: &'static str = "nomove";
 
 // Represents known policies as an integral type.
 pub const POLICY_IGNORE: Policy = 0;
@@ -33,6 +43,11 @@ pub const POLICY_NOADD: Policy = 1 << 0;
 pub const POLICY_NODELETE: Policy = 1 << 1;
 pub const POLICY_NOMODIFY: Policy = 1 << 2;
 pub const POLICY_IMMUTABLE: Policy = POLICY_NOADD | POLICY_NODELETE | POLICY_NOMODIFY;
+// Opts a path out of add/delete pairing into a single MOVED violation;
+// trees that expect wholesale reorganization can set this so a
+// rename/move is reported as the plain `+`/`-` pair it already was
+// instead of being consolidated.
+pub const POLICY_NOMOVE: Policy = 1 << 3;
 
 // Represents a zakopane config. Please consult the documentation.
 pub struct Config {
@@ -40,8 +55,8 @@ pub struct Config {
     policies: Policies,
 }
 
-// Borrows the string representation of one policy `token` and returns
-// the equivalent integral representation.
+// Borrows the string representation of one built-in policy `token` and
+// returns the equivalent integral representation.
 fn policy_token_as_int(token: &str) -> Result<Policy, ZakocmpError> {
     match token {
         POLICY_REPR_IGNORE => Ok(POLICY_IGNORE),
@@ -49,17 +64,53 @@ fn policy_token_as_int(token: &str) -> Result<Policy, ZakocmpError> {
         POLICY_REPR_NODELETE => Ok(POLICY_NODELETE),
         POLICY_REPR_NOMODIFY => Ok(POLICY_NOMODIFY),
         POLICY_REPR_IMMUTABLE => Ok(POLICY_IMMUTABLE),
+        POLICY_REPR_NOMOVE => Ok(POLICY_NOMOVE),
         _ => Err(ZakocmpError::Config(format!("bad token: ``{}''", token))),
     }
 }
 
+// Resolves a single policy token to its integral representation, either
+// directly (a built-in keyword) or by expanding it as an alias defined
+// in `definitions`. `resolving` tracks the chain of alias names
+// currently being expanded so a self-referential alias is rejected
+// instead of recursing forever.
+fn token_as_int(
+    token: &str,
+    definitions: &Definitions,
+    resolving: &mut Vec<String>,
+) -> Result<Policy, ZakocmpError> {
+    if let Ok(policy) = policy_token_as_int(token) {
+        return Ok(policy);
+    }
+    if resolving.iter().any(|name| name == token) {
+        resolving.push(token.to_owned());
+        return Err(ZakocmpError::Config(format!(
+            "self-referential definition: {}",
+            resolving.join(" -> ")
+        )));
+    }
+    let alias_expr = definitions
+        .get(token)
+        .ok_or_else(|| ZakocmpError::Config(format!("bad token: ``{}''", token)))?;
+    resolving.push(token.to_owned());
+    let resolved = policy_tokens_as_int(alias_expr, definitions, resolving)?;
+    resolving.pop();
+    Ok(resolved)
+}
+
 // Borrows the string representation of a combined `policy` and returns
 // the equivalent integral representation. This function expects
-// `policy` to comprise one or more policy tokens separated by commas.
-fn policy_tokens_as_int(policy: &str) -> Result<Policy, ZakocmpError> {
+// `policy` to comprise one or more policy tokens separated by commas,
+// each either a built-in keyword or an alias looked up in
+// `definitions`.
+fn policy_tokens_as_int(
+    policy: &str,
+    definitions: &Definitions,
+    resolving: &mut Vec<String>,
+) -> Result<Policy, ZakocmpError> {
     let policy_ints: Vec<Policy> = policy
         .split(",")
-        .map(|tok| policy_token_as_int(tok))
+        .map(|tok| token_as_int(tok, definitions, resolving))
         .collect::<Result<Vec<Policy>, ZakocmpError>>()?;
     Ok(policy_ints
         .iter()
@@ -72,22 +123,26 @@ fn policy_tokens_as_int(policy: &str) -> Result<Policy, ZakocmpError> {
 fn policy_tuple_from_yaml(
     ypath: &Yaml,
     policy_tokens: &Yaml,
-) -> Result<(String, Policy), ZakocmpError> {
+    definitions: &Definitions,
+) -> Result<(Matcher, Policy), ZakocmpError> {
     let path: String = match ypath.as_str() {
         Some(string) => string.to_owned(),
         None => return Err(ZakocmpError::Config("malformed path?".to_string())),
     };
     let policy: Policy = match policy_tokens.as_str() {
-        Some(string) => policy_tokens_as_int(string)?,
+        Some(string) => policy_tokens_as_int(string, definitions, &mut Vec::new())
+            .map_err(|error| ZakocmpError::Config(format!("rule `{}`: {}", path, error)))?,
         None => return Err(ZakocmpError::Config("malformed policy?".to_string())),
     };
-    Ok((path, policy))
+    let matcher = Matcher::compile(&path)
+        .map_err(|error| ZakocmpError::Config(format!("rule `{}`: {}", path, error)))?;
+    Ok((matcher, policy))
 }
 
 // Borrows the YAML representation of a zakopane config and returns the
 // corresponding Policies. The return value can be benignly
 // empty (e.g. if the present config elects not to specify any rules).
-fn policies_from_yaml(doc: &Yaml) -> Result<Policies, ZakocmpError> {
+fn policies_from_yaml(doc: &Yaml, definitions: &Definitions) -> Result<Policies, ZakocmpError> {
     let policies_map_yaml = &doc[POLICIES_KEY];
     if policies_map_yaml.is_badvalue() {
         // Assumes the config may be benignly devoid of specific
@@ -95,32 +150,104 @@ fn policies_from_yaml(doc: &Yaml) -> Result<Policies, ZakocmpError> {
         return Ok(vec![]);
     }
     // Otherwise, iterates over the policies map. Each entry in the
-    // policies map correlates a path prefix to a comma-separated list
-    // of policies.
+    // policies map correlates a path key (prefix, glob, or regex) to a
+    // comma-separated list of policies.
     let policies_map: &yaml_rust::yaml::Hash = match policies_map_yaml.as_hash() {
         Some(map) => map,
         None => return Err(ZakocmpError::Config("malformed policies".to_string())),
     };
-    let mut policies: Policies = policies_map
-        .into_iter()
-        .map(|pair| policy_tuple_from_yaml(&pair.0, &pair.1))
-        .collect::<Result<Policies, ZakocmpError>>()?;
-    policies.sort_unstable_by_key(|pair| pair.0.to_owned());
+    // Sorts by the raw YAML key before compiling, since a compiled
+    // Matcher (in particular a Regex) has no single sortable
+    // representation; this is what gives `match_policy` a
+    // deterministic tie-break order.
+    let mut entries: Vec<(&Yaml, &Yaml)> = policies_map.into_iter().collect();
+    entries.sort_unstable_by(|a, b| a.0.as_str().unwrap_or("").cmp(b.0.as_str().unwrap_or("")));
+
+    // Walks every entry regardless of earlier failures, so a config
+    // with several malformed rules gets reported all at once instead
+    // of one edit-run cycle per typo.
+    let mut policies = Policies::new();
+    let mut errors = Vec::new();
+    for (ypath, policy_tokens) in entries {
+        match policy_tuple_from_yaml(ypath, policy_tokens, definitions) {
+            Ok(tuple) => policies.push(tuple),
+            Err(error) => errors.push(error),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ZakocmpError::Multiple(errors));
+    }
     Ok(policies)
 }
 
 // Borrows the YAML representation of a zakopane config and returns the
-// integral default-policy defined within.
-fn default_policy_from_yaml(doc: &Yaml) -> Result<Policy, ZakocmpError> {
+// integral default-policy defined within, or None if this file doesn't
+// set one (so a file that merely `include`s a base config and adds a
+// few extra policies doesn't have to repeat its default-policy too).
+fn default_policy_from_yaml(
+    doc: &Yaml,
+    definitions: &Definitions,
+) -> Result<Option<Policy>, ZakocmpError> {
     let default_policy_yaml = &doc[DEFAULT_POLICY_KEY];
     if default_policy_yaml.is_badvalue() {
-        return Err(ZakocmpError::Config(DEFAULT_POLICY_KEY.to_string()));
+        return Ok(None);
     }
     let default_policy: Policy = match default_policy_yaml.as_str() {
         None => return Err(ZakocmpError::Config(DEFAULT_POLICY_KEY.to_string())),
-        Some(token) => policy_tokens_as_int(&token),
+        Some(token) => policy_tokens_as_int(&token, definitions, &mut Vec::new()),
     }?;
-    Ok(default_policy)
+    Ok(Some(default_policy))
+}
+
+// Borrows the YAML representation of a zakopane config and returns its
+// top-level `definitions` map of alias name to (still unresolved)
+// token expression. The return value can be benignly empty.
+fn definitions_from_yaml(doc: &Yaml) -> Result<Definitions, ZakocmpError> {
+    let definitions_yaml = &doc[DEFINITIONS_KEY];
+    if definitions_yaml.is_badvalue() {
+        return Ok(Definitions::new());
+    }
+    let definitions_map: &yaml_rust::yaml::Hash = match definitions_yaml.as_hash() {
+        Some(map) => map,
+        None => return Err(ZakocmpError::Config("malformed definitions".to_string())),
+    };
+
+    let mut definitions = Definitions::new();
+    for (yname, yexpr) in definitions_map {
+        let name = yname
+            .as_str()
+            .ok_or_else(|| ZakocmpError::Config("malformed definition name".to_string()))?;
+        let expr = yexpr
+            .as_str()
+            .ok_or_else(|| ZakocmpError::Config("malformed definition value".to_string()))?;
+        definitions.insert(name.to_owned(), expr.to_owned());
+    }
+    Ok(definitions)
+}
+
+// Borrows the YAML representation of a zakopane config and returns the
+// paths named by its top-level `include` key, resolved relative to
+// `base_dir` (the including file's own directory). The return value is
+// benignly empty if the config doesn't include anything.
+fn include_paths_from_yaml(
+    doc: &Yaml,
+    base_dir: &std::path::Path,
+) -> Result<Vec<std::path::PathBuf>, ZakocmpError> {
+    let include_yaml = &doc[INCLUDE_KEY];
+    if include_yaml.is_badvalue() {
+        return Ok(vec![]);
+    }
+    let entries = match include_yaml.as_vec() {
+        Some(entries) => entries,
+        None => return Err(ZakocmpError::Config("malformed include".to_string())),
+    };
+    entries
+        .iter()
+        .map(|entry| match entry.as_str() {
+            Some(relative_path) => Ok(base_dir.join(relative_path)),
+            None => Err(ZakocmpError::Config("malformed include entry".to_string())),
+        })
+        .collect()
 }
 
 // Interprets |config_contents| as YAML and returns the first document
@@ -138,42 +265,103 @@ fn read_yaml(config_contents: &str) -> Result<Option<Yaml>, ZakocmpError> {
     Ok(Some(docs[0].clone()))
 }
 
-// Returns the default policy for this invocation.
-fn get_default_policy(
-    options: &CliOptions,
+// Returns any additional policies for this invocation.
+fn get_policies(
     yaml_config: &Option<Yaml>,
-) -> Result<Policy, ZakocmpError> {
-    if let Some(default_from_cli) = options.default_policy {
-        return policy_tokens_as_int(default_from_cli);
-    } else if let Some(yaml) = yaml_config {
-        return default_policy_from_yaml(yaml);
+    definitions: &Definitions,
+) -> Result<Policies, ZakocmpError> {
+    match yaml_config {
+        Some(doc) => policies_from_yaml(doc, definitions),
+        None => Ok(Policies::new()),
     }
-    Ok(POLICY_IMMUTABLE)
 }
 
-// Returns any additional policies for this invocation.
-fn get_policies(yaml_config: &Option<Yaml>) -> Result<Policies, ZakocmpError> {
-    match yaml_config {
-        Some(doc) => policies_from_yaml(doc),
-        None => Ok(Policies::new()),
+// Loads `path`, merges its includes (relative to `path`'s own
+// directory) in first, then appends its own policies and applies its
+// own default-policy (if any) on top. `loading` tracks the chain of
+// paths currently being loaded so a cyclic include is rejected instead
+// of recursing forever. A file's `definitions` aliases are only ever
+// visible to that same file's own `policies`/`default-policy` entries,
+// not to files that include it or that it includes.
+fn load_config_file(
+    path: &std::path::Path,
+    loading: &mut Vec<std::path::PathBuf>,
+    default_policy: &mut Option<Policy>,
+    policies: &mut Policies,
+) -> Result<(), ZakocmpError> {
+    let path_buf = path.to_path_buf();
+    if loading.contains(&path_buf) {
+        let cycle: Vec<String> = loading
+            .iter()
+            .chain(std::iter::once(&path_buf))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(ZakocmpError::Config(format!(
+            "cyclic include: {}",
+            cycle.join(" -> ")
+        )));
     }
+    loading.push(path_buf);
+
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| ZakocmpError::Config(format!("non-UTF-8 config path: {}", path.display())))?;
+    let config_contents = crate::helpers::ingest_file(path_str)?;
+    let yaml_config = read_yaml(&config_contents)?;
+
+    if let Some(yaml) = &yaml_config {
+        let base_dir = path
+            .parent()
+            .map(std::path::Path::to_path_buf)
+            .unwrap_or_default();
+        for include_path in include_paths_from_yaml(yaml, &base_dir)? {
+            load_config_file(&include_path, loading, default_policy, policies)?;
+        }
+    }
+
+    let definitions = match &yaml_config {
+        Some(yaml) => definitions_from_yaml(yaml)?,
+        None => Definitions::new(),
+    };
+    if let Some(yaml) = &yaml_config {
+        if let Some(overriding_default) = default_policy_from_yaml(yaml, &definitions)? {
+            *default_policy = Some(overriding_default);
+        }
+    }
+    policies.extend(get_policies(&yaml_config, &definitions)?);
+
+    loading.pop();
+    Ok(())
 }
 
 impl Config {
-    // Borrows the string representation of a zakopane config and
-    // returns a corresponding Config.
+    // Borrows a zakopane CLI invocation and returns the corresponding
+    // Config. `options.config_path` (if given) is merged together
+    // with whatever it `include`s, relative to its own directory:
+    // later/included files' `policies` entries are appended, and
+    // their `default-policy` (if set) overrides whatever came before.
+    // A `default-policy` given on the command line always wins over
+    // anything written in the config.
     pub fn new(options: &CliOptions) -> Result<Config, ZakocmpError> {
-        let yaml_config: Option<Yaml> = match options.config_path {
-            Some(path) => {
-                let config = crate::helpers::ingest_file(path)?;
-                read_yaml(&config)?
+        let mut default_policy: Option<Policy> = None;
+        let mut policies = Policies::new();
+        if let Some(path) = options.config_path {
+            let mut loading = Vec::new();
+            load_config_file(
+                std::path::Path::new(path),
+                &mut loading,
+                &mut default_policy,
+                &mut policies,
+            )?;
+        }
+
+        let default_policy = match options.default_policy {
+            Some(default_from_cli) => {
+                policy_tokens_as_int(default_from_cli, &Definitions::new(), &mut Vec::new())?
             }
-            None => None,
+            None => default_policy.unwrap_or(POLICY_IMMUTABLE),
         };
 
-        let default_policy = get_default_policy(&options, &yaml_config)?;
-        let policies = get_policies(&yaml_config)?;
-
         Ok(Config {
             default_policy: default_policy,
             policies: policies,
@@ -187,25 +375,36 @@ impl Config {
     }
 
     // Borrows a `path` and returns the best-matched policy that
-    // applies. This function returns an owned tuple of the
-    // (closest-matched path expression, integral policy).
+    // applies (plain prefix, glob, or regex). This function returns a
+    // tuple of the (closest-matched rule's key, integral policy).
+    // Overlapping matches are resolved by `Matcher::specificity`: a
+    // later match only displaces an earlier one if it's strictly more
+    // specific, so ties between equally-specific matchers favor
+    // whichever comes first: within one file, whichever sorts first
+    // (see `policies_from_yaml`'s sort); across files, whichever file
+    // was loaded earlier (an included file before its includer).
     //
     // This function represents the default-policy fallback by
     // returning the tuple consisting of an empty &str and the
     // default policy.
     pub fn match_policy(&self, path: &str) -> (&str, Policy) {
-        let mut best_match_path: &str = "";
+        let mut best_match_key: &str = "";
         let mut best_match_policy: Policy = 0;
-        for (prefix, policy) in self.policies.iter() {
-            if path.starts_with(prefix) && prefix.len() > best_match_path.len() {
-                best_match_path = prefix;
+        let mut best_specificity: usize = 0;
+        for (matcher, policy) in self.policies.iter() {
+            if !matcher.matches(path) {
+                continue;
+            }
+            if best_match_key.is_empty() || matcher.specificity() > best_specificity {
+                best_specificity = matcher.specificity();
+                best_match_key = matcher.key();
                 best_match_policy = *policy;
             }
         }
-        if best_match_path.len() == 0 {
+        if best_match_key.is_empty() {
             return ("", self.default_policy);
         }
-        return (best_match_path, best_match_policy);
+        return (best_match_key, best_match_policy);
     }
 }
 
@@ -219,8 +418,16 @@ pub mod test_support {
         default_policy: Option<&'a str>,
     ) -> CliOptions<'a> {
         CliOptions {
+            old_snapshot_path: "",
+            new_snapshot_path: "",
             config_path: config_path,
             default_policy: default_policy,
+            baseline_path: None,
+            write_baseline: false,
+            output_format: crate::structs::OutputFormat::Full,
+            review: false,
+            review_command: None,
+            quiet: false,
         }
     }
 
@@ -239,30 +446,130 @@ mod tests {
 
     #[test]
     fn policy_token_bare() {
-        let policy: Policy = policy_tokens_as_int(&"noadd").unwrap();
+        let definitions = Definitions::new();
+        let policy: Policy = policy_tokens_as_int(&"noadd", &definitions, &mut Vec::new()).unwrap();
         assert_eq!(policy, POLICY_NOADD);
 
-        let policy: Policy = policy_tokens_as_int(&"nodelete").unwrap();
+        let policy: Policy =
+            policy_tokens_as_int(&"nodelete", &definitions, &mut Vec::new()).unwrap();
         assert_eq!(policy, POLICY_NODELETE);
 
-        let policy: Policy = policy_tokens_as_int(&"nomodify").unwrap();
+        let policy: Policy =
+            policy_tokens_as_int(&"nomodify", &definitions, &mut Vec::new()).unwrap();
         assert_eq!(policy, POLICY_NOMODIFY);
     }
 
     #[test]
     fn policy_tokens_can_combo() {
-        let policy: Policy = policy_tokens_as_int(&"noadd,nodelete").unwrap();
+        let policy: Policy =
+            policy_tokens_as_int(&"noadd,nodelete", &Definitions::new(), &mut Vec::new()).unwrap();
         assert_eq!(policy, POLICY_NOADD | POLICY_NODELETE);
     }
 
     #[test]
     fn policy_tokens_can_repeat() {
-        let policy: Policy =
-            policy_tokens_as_int(&"noadd,noadd,noadd,noadd,nodelete,nodelete,nodelete,noadd")
-                .unwrap();
+        let policy: Policy = policy_tokens_as_int(
+            &"noadd,noadd,noadd,noadd,nodelete,nodelete,nodelete,noadd",
+            &Definitions::new(),
+            &mut Vec::new(),
+        )
+        .unwrap();
         assert_eq!(policy, POLICY_NOADD | POLICY_NODELETE);
     }
 
+    #[test]
+    fn policy_tokens_resolve_aliases_from_definitions() {
+        let mut definitions = Definitions::new();
+        definitions.insert("archive".to_string(), "noadd,nodelete".to_string());
+        let policy: Policy =
+            policy_tokens_as_int(&"archive,nomodify", &definitions, &mut Vec::new()).unwrap();
+        assert_eq!(policy, POLICY_IMMUTABLE);
+    }
+
+    #[test]
+    fn self_referential_definition_is_rejected() {
+        let mut definitions = Definitions::new();
+        definitions.insert("circular".to_string(), "circular".to_string());
+        match policy_tokens_as_int(&"circular", &definitions, &mut Vec::new()) {
+            Err(ZakocmpError::Config(message)) => {
+                assert!(message.contains("self-referential definition"))
+            }
+            other => panic!("expected self-referential error, got {:?}", other.is_err()),
+        }
+    }
+
+    #[test]
+    fn policies_from_yaml_accumulates_every_bad_rule() {
+        let yaml = read_yaml(
+            r#"
+policies:
+  ./a: nodlete
+  ./b: nomodify
+  ./c: bogustoken
+"#,
+        )
+        .unwrap()
+        .unwrap();
+        match policies_from_yaml(&yaml, &Definitions::new()) {
+            Err(ZakocmpError::Multiple(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("expected ZakocmpError::Multiple, got {:?}", other.is_err()),
+        }
+    }
+
+    #[test]
+    fn policies_from_yaml_resolves_definitions() {
+        let yaml = read_yaml(
+            r#"
+definitions:
+  archive: noadd,nodelete
+policies:
+  ./a: archive
+"#,
+        )
+        .unwrap()
+        .unwrap();
+        let definitions = definitions_from_yaml(&yaml).unwrap();
+        let policies = policies_from_yaml(&yaml, &definitions).unwrap();
+        assert_eq!(policies.len(), 1);
+        let (matcher, policy) = &policies[0];
+        assert_eq!(matcher.key(), "./a");
+        assert_eq!(*policy, POLICY_NOADD | POLICY_NODELETE);
+    }
+
+    #[test]
+    fn include_paths_from_yaml_resolves_relative_to_base_dir() {
+        let yaml = read_yaml(
+            r#"
+include:
+  - base.yaml
+  - ../shared/extra.yaml
+"#,
+        )
+        .unwrap()
+        .unwrap();
+        let base_dir = std::path::Path::new("/etc/zakocmp.d");
+        let paths = include_paths_from_yaml(&yaml, base_dir).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                std::path::PathBuf::from("/etc/zakocmp.d/base.yaml"),
+                std::path::PathBuf::from("/etc/zakocmp.d/../shared/extra.yaml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_config_file_rejects_cyclic_include() {
+        let path = std::path::Path::new("/tmp/this-path-need-not-exist.yaml");
+        let mut loading = vec![path.to_path_buf()];
+        let mut default_policy = None;
+        let mut policies = Policies::new();
+        match load_config_file(path, &mut loading, &mut default_policy, &mut policies) {
+            Err(ZakocmpError::Config(message)) => assert!(message.contains("cyclic include")),
+            other => panic!("expected cyclic include error, got {:?}", other.is_err()),
+        }
+    }
+
     #[test]
     fn config_must_not_be_obviously_malformed() {
         let config_path = test_support::data_path("flagrantly-invalid-yaml");
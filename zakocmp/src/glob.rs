@@ -0,0 +1,179 @@
+// This module implements minimal glob matching for zakocmp policy
+// keys. A key may still be a plain literal prefix (today's behavior,
+// unchanged), or it may contain wildcards: `*` and `?` match within a
+// single path segment, `[...]` matches a character class, and `**`
+// matches zero or more whole path segments (e.g. `**/*.jpg` matches
+// any `.jpg` file at any depth).
+
+// A compiled policy key, ready to be matched against snapshot paths
+// without re-parsing. Compiling once at `Config::new` time keeps
+// `Config::match_policy` cheap even when many paths are checked
+// against the same config. Ranking a `Pattern` against other matchers
+// is `crate::matcher::Matcher`'s job, not this module's.
+#[derive(Debug)]
+pub struct Pattern {
+    key: String,
+    segments: Vec<String>,
+}
+
+fn is_wildcard_char(c: char) -> bool {
+    c == '*' || c == '?' || c == '['
+}
+
+impl Pattern {
+    pub fn compile(key: &str) -> Pattern {
+        let segments: Vec<String> = key.split('/').map(str::to_owned).collect();
+        Pattern {
+            key: key.to_owned(),
+            segments,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn has_wildcards(&self) -> bool {
+        self.segments.iter().any(|segment| segment.contains(is_wildcard_char))
+    }
+
+    // Returns whether `path` matches this pattern. A pattern with no
+    // wildcards at all falls back to a plain `starts_with` prefix
+    // check, so today's literal-prefix keys behave exactly as before.
+    pub fn matches(&self, path: &str) -> bool {
+        if !self.has_wildcards() {
+            return path.starts_with(&self.key);
+        }
+        let path_segments: Vec<&str> = path.split('/').collect();
+        segments_match(&self.segments, &path_segments)
+    }
+}
+
+// Matches a sequence of pattern segments against a sequence of path
+// segments, expanding `**` to zero or more path segments.
+fn segments_match(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((head, rest)) if head == "**" => {
+            segments_match(rest, path) || (!path.is_empty() && segments_match(pattern, &path[1..]))
+        }
+        Some((head, rest)) => match path.split_first() {
+            Some((first, path_rest)) => segment_matches(head, first) && segments_match(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+// Matches a single pattern segment (no `/`) against a single path
+// segment, supporting `*`, `?`, and `[...]` character classes.
+fn segment_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    chars_match(&pattern, &text)
+}
+
+fn chars_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((&'*', rest)) => {
+            chars_match(rest, text) || (!text.is_empty() && chars_match(pattern, &text[1..]))
+        }
+        Some((&'?', rest)) => !text.is_empty() && chars_match(rest, &text[1..]),
+        Some((&'[', _)) => match parse_class(pattern) {
+            Some((negated, members, consumed)) => {
+                !text.is_empty()
+                    && (members.contains(&text[0]) != negated)
+                    && chars_match(&pattern[consumed..], &text[1..])
+            }
+            // No closing `]`: treat `[` as a literal character.
+            None => !text.is_empty() && text[0] == '[' && chars_match(&pattern[1..], &text[1..]),
+        },
+        Some((&c, rest)) => !text.is_empty() && text[0] == c && chars_match(rest, &text[1..]),
+    }
+}
+
+// Parses a `[...]` character class starting at `pattern[0] == '['`.
+// Returns the class's membership (negated or not, plus each matching
+// character with ranges like `a-z` expanded) and how many chars of
+// `pattern` the class consumed, or None if there's no closing `]`.
+fn parse_class(pattern: &[char]) -> Option<(bool, Vec<char>, usize)> {
+    let mut i = 1;
+    let negated = match pattern.get(i) {
+        Some(&'!') | Some(&'^') => {
+            i += 1;
+            true
+        }
+        _ => false,
+    };
+    let body_start = i;
+    while i < pattern.len() && pattern[i] != ']' {
+        i += 1;
+    }
+    if i >= pattern.len() {
+        return None;
+    }
+    let body = &pattern[body_start..i];
+
+    let mut members = Vec::new();
+    let mut j = 0;
+    while j < body.len() {
+        if j + 2 < body.len() && body[j + 1] == '-' {
+            let (lo, hi) = (body[j], body[j + 2]);
+            let mut c = lo;
+            while c <= hi {
+                members.push(c);
+                c = ((c as u8) + 1) as char;
+            }
+            j += 3;
+        } else {
+            members.push(body[j]);
+            j += 1;
+        }
+    }
+    Some((negated, members, i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_pattern_behaves_as_prefix() {
+        let pattern = Pattern::compile("./Pictures");
+        assert!(pattern.matches("./Pictures/2020/food.md"));
+        assert!(!pattern.matches("./Music/2020/food.md"));
+    }
+
+    #[test]
+    fn star_matches_within_one_segment_only() {
+        let pattern = Pattern::compile("./Pictures/*.jpg");
+        assert!(pattern.matches("./Pictures/yano.jpg"));
+        assert!(!pattern.matches("./Pictures/2020/yano.jpg"));
+    }
+
+    #[test]
+    fn double_star_matches_any_depth() {
+        let pattern = Pattern::compile("./Pictures/**/*.jpg");
+        assert!(pattern.matches("./Pictures/yano.jpg"));
+        assert!(pattern.matches("./Pictures/2020/summer/yano.jpg"));
+        assert!(!pattern.matches("./Pictures/2020/summer/yano.png"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        let pattern = Pattern::compile("./log.?");
+        assert!(pattern.matches("./log.0"));
+        assert!(!pattern.matches("./log.10"));
+    }
+
+    #[test]
+    fn character_class_matches_and_negates() {
+        let pattern = Pattern::compile("./log.[0-2]");
+        assert!(pattern.matches("./log.1"));
+        assert!(!pattern.matches("./log.9"));
+
+        let pattern = Pattern::compile("./log.[!0-2]");
+        assert!(pattern.matches("./log.9"));
+        assert!(!pattern.matches("./log.1"));
+    }
+}
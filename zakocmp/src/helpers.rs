@@ -4,11 +4,89 @@ use std::io::Read;
 
 use crate::structs::ZakocmpError;
 
-// Ingests the contents of a file.
-pub fn ingest_file(path: &str) -> Result<String, ZakocmpError> {
-    let mut file = std::fs::File::open(std::path::Path::new(path)).map_err(ZakocmpError::Io)?;
+// Which (if any) compression `open_file` detected on a path, driving
+// which decoder wraps the underlying file handle.
+enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+// Detects gzip/zstd compression by file extension first (cheap, no
+// I/O), falling back to sniffing the leading magic bytes for files
+// whose extension doesn't give it away.
+fn detect_compression(file: &mut std::fs::File, path: &str) -> Result<Compression, ZakocmpError> {
+    if path.ends_with(".gz") {
+        return Ok(Compression::Gzip);
+    }
+    if path.ends_with(".zst") {
+        return Ok(Compression::Zstd);
+    }
+
+    let io_error_at = |e| ZakocmpError::IoWithPath(std::path::PathBuf::from(path), e);
+    let mut magic = [0u8; 4];
+    let bytes_read = file.read(&mut magic).map_err(io_error_at)?;
+    std::io::Seek::seek(file, std::io::SeekFrom::Start(0)).map_err(io_error_at)?;
+
+    if bytes_read >= 2 && magic[0..2] == [0x1f, 0x8b] {
+        Ok(Compression::Gzip)
+    } else if bytes_read >= 4 && magic[0..4] == [0x28, 0xb5, 0x2f, 0xfd] {
+        Ok(Compression::Zstd)
+    } else {
+        Ok(Compression::None)
+    }
+}
+
+// Reads `reader` to the end, whatever it is (an open file, stdin, a
+// pipe, ...). The core of `ingest_file`, split out so callers that
+// already have a `Read` in hand (e.g. stdin, for piping a snapshot in
+// via `-`) don't have to go through a file path to use it.
+pub fn ingest_reader<R: std::io::Read>(mut reader: R) -> Result<String, ZakocmpError> {
     let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .map_err(ZakocmpError::Io)?;
+    reader.read_to_string(&mut contents).map_err(ZakocmpError::Io)?;
     Ok(contents)
 }
+
+// Ingests the contents of a file, transparently decompressing it
+// first if it's gzip- or zstd-compressed (see `open_file`).
+pub fn ingest_file(path: &str) -> Result<String, ZakocmpError> {
+    let reader = open_file(path)?;
+    ingest_reader(reader).map_err(|e| match e {
+        ZakocmpError::Io(io_error) => {
+            ZakocmpError::IoWithPath(std::path::PathBuf::from(path), io_error)
+        }
+        other => other,
+    })
+}
+
+// Opens a file for streaming, line-by-line ingestion (e.g. into
+// `Snapshot::from_reader`), as opposed to `ingest_file`'s full slurp.
+// Transparently decompresses gzip (`.gz`) and zstd (`.zst`) files so
+// the rest of the comparison pipeline never has to care whether a
+// config or snapshot was stored compressed.
+pub fn open_file(path: &str) -> Result<std::io::BufReader<Box<dyn std::io::Read>>, ZakocmpError> {
+    let mut file = std::fs::File::open(std::path::Path::new(path))
+        .map_err(|e| ZakocmpError::IoWithPath(std::path::PathBuf::from(path), e))?;
+    let reader: Box<dyn std::io::Read> = match detect_compression(&mut file, path)? {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(flate2::read::GzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::Decoder::new(file).map_err(|e| {
+            ZakocmpError::Decompress(format!("failed to decompress \"{}\": {}", path, e))
+        })?),
+    };
+    Ok(std::io::BufReader::new(reader))
+}
+
+// Opens a file for streaming, line-by-line ingestion, yielding each
+// line as it's read rather than `ingest_file`'s one-shot slurp. Meant
+// for files too large to comfortably hold entirely in memory (e.g. a
+// snapshot of a large tree), where peak memory should stay
+// proportional to the longest line rather than the whole file.
+pub fn ingest_lines(
+    path: &str,
+) -> Result<impl Iterator<Item = Result<String, ZakocmpError>>, ZakocmpError> {
+    let reader = open_file(path)?;
+    let path_buf = std::path::PathBuf::from(path);
+    Ok(std::io::BufRead::lines(reader)
+        .map(move |line| line.map_err(|e| ZakocmpError::IoWithPath(path_buf.clone(), e))))
+}
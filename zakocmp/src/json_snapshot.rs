@@ -0,0 +1,34 @@
+// Defines zakopane's JSON snapshot format: the same per-path checksum
+// map the legacy text format carries, but as structured data, plus
+// metadata (format version, algorithm, root, timestamp) that the legacy
+// header could only express as loosely-parsed free-form text. It does
+// not record which zakocmp release wrote it.
+// Borrows the "accept your own machine-readable output as input" idea
+// so downstream tooling can produce (or consume) a zakopane snapshot
+// without zakocmp's bespoke text grammar.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+// The JSON snapshot format's own version, independent of zakocmp's
+// crate version, so the format can evolve without tying to a release.
+pub const JSON_SNAPSHOT_VERSION: u32 = 1;
+
+// The on-disk shape of a JSON snapshot. `snapshot::Snapshot` is built
+// from this (see `snapshot::Snapshot::from_json_reader`) rather than
+// exposing this struct's fields directly to callers outside the
+// crate.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonSnapshot {
+    pub version: u32,
+    // The name of the digest algorithm the checksums below were
+    // produced with (e.g. "sha256"), matching `Algorithm::name()`.
+    pub algorithm: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub root: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timestamp: Option<String>,
+    // Maps each scanned path to its checksum.
+    pub contents: HashMap<String, String>,
+}
@@ -1,19 +1,95 @@
+pub mod baseline;
 pub mod config;
+pub mod glob;
 pub mod helpers;
+pub mod json_snapshot;
+pub mod matcher;
+pub mod review;
 pub mod snapshot;
 pub mod structs;
 pub mod violations;
 
 use config::Config;
 use snapshot::Snapshot;
+use structs::ZakocmpError;
 use violations::Violations;
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+// Pairs deleted paths against added paths that share an identical
+// checksum, treating the pair as a move/rename instead of an unrelated
+// delete-plus-add. Pairing is sorted and index-matched (never a
+// cross-product of every same-checksum path), so the all-zero
+// placeholder checksums common in tests don't collapse into a
+// combinatorial explosion of "moves". Returns the paths on each side
+// that were paired off, which must then be excluded from plain
+// `+`/`-` reporting.
+fn pair_moves(
+    config: &Config,
+    older_snapshot: &Snapshot,
+    newer_snapshot: &Snapshot,
+    violations: &mut Violations,
+) -> (HashSet<String>, HashSet<String>) {
+    let mut deleted_by_checksum: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (path, checksum) in older_snapshot.iter() {
+        if newer_snapshot.get(path).is_none() {
+            deleted_by_checksum
+                .entry(checksum)
+                .or_insert_with(Vec::new)
+                .push(path);
+        }
+    }
+    let mut added_by_checksum: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (path, checksum) in newer_snapshot.iter() {
+        if older_snapshot.get(path).is_none() {
+            added_by_checksum
+                .entry(checksum)
+                .or_insert_with(Vec::new)
+                .push(path);
+        }
+    }
+
+    let mut paired_old = HashSet::new();
+    let mut paired_new = HashSet::new();
+    for (checksum, mut old_paths) in deleted_by_checksum {
+        let added_paths = match added_by_checksum.get(checksum) {
+            Some(paths) => paths,
+            None => continue,
+        };
+        old_paths.sort_unstable();
+        let mut new_paths = added_paths.clone();
+        new_paths.sort_unstable();
+
+        let pair_count = old_paths.len().min(new_paths.len());
+        for i in 0..pair_count {
+            let (_old_rule, old_policy) = config.match_policy(old_paths[i]);
+            let (_new_rule, new_policy) = config.match_policy(new_paths[i]);
+            if (old_policy & config::POLICY_NOMOVE) != 0 || (new_policy & config::POLICY_NOMOVE) != 0
+            {
+                continue;
+            }
+            // Only worth reporting as a move if the plain delete or
+            // add it stands in for would itself have been flagged.
+            if (old_policy & config::POLICY_NODELETE) == 0 && (new_policy & config::POLICY_NOADD) == 0
+            {
+                continue;
+            }
+            violations.moved(old_paths[i], new_paths[i]);
+            paired_old.insert(old_paths[i].to_string());
+            paired_new.insert(new_paths[i].to_string());
+        }
+    }
+    (paired_old, paired_new)
+}
+
 // Compares the older snapshot against the newer snapshot, accruing
 // violations where discrepancies are detected per policy.
 fn check_modifications_and_deletions(
     config: &Config,
     older_snapshot: &Snapshot,
     newer_snapshot: &Snapshot,
+    paired_old: &HashSet<String>,
     violations: &mut Violations,
 ) {
     for (path, checksum) in older_snapshot.iter() {
@@ -29,6 +105,9 @@ fn check_modifications_and_deletions(
                 }
             }
             None => {
+                if paired_old.contains(path) {
+                    continue;
+                }
                 if (policy & config::POLICY_NODELETE) != 0 {
                     violations.add(path, violations::DELETED).unwrap();
                 }
@@ -43,6 +122,7 @@ fn check_additions(
     config: &Config,
     older_snapshot: &Snapshot,
     newer_snapshot: &Snapshot,
+    paired_new: &HashSet<String>,
     violations: &mut Violations,
 ) {
     for (path, _checksum) in newer_snapshot.iter() {
@@ -54,6 +134,9 @@ fn check_additions(
         match older_snapshot.get(path) {
             Some(_older_checksum) => (),
             None => {
+                if paired_new.contains(path) {
+                    continue;
+                }
                 if (policy & config::POLICY_NOADD) != 0 {
                     violations.add(path, violations::ADDED).unwrap();
                 }
@@ -64,11 +147,113 @@ fn check_additions(
 
 // The main entry point of the zakocmp executable.
 // Accepts a well-formed Config, older Snapshot, and newer Snapshot.
-// Returns a Violation struct.
-pub fn enter(config: &Config, older_snapshot: &Snapshot, newer_snapshot: &Snapshot) -> Violations {
+// Returns a Violation struct, or an error if the two snapshots were
+// built with different checksum algorithms (comparing them path-for-
+// path would otherwise report every file as modified).
+pub fn enter(
+    config: &Config,
+    older_snapshot: &Snapshot,
+    newer_snapshot: &Snapshot,
+) -> Result<Violations, ZakocmpError> {
+    snapshot::ensure_same_algorithm(older_snapshot, newer_snapshot)?;
+    if let Some(warning) = snapshot::root_mismatch_warning(older_snapshot, newer_snapshot) {
+        eprintln!("{}", warning);
+    }
+
     let mut violations = Violations::new();
-    check_modifications_and_deletions(&config, &older_snapshot, &newer_snapshot, &mut violations);
-    check_additions(&config, &older_snapshot, &newer_snapshot, &mut violations);
+    let (paired_old, paired_new) =
+        pair_moves(&config, &older_snapshot, &newer_snapshot, &mut violations);
+    check_modifications_and_deletions(
+        &config,
+        &older_snapshot,
+        &newer_snapshot,
+        &paired_old,
+        &mut violations,
+    );
+    check_additions(
+        &config,
+        &older_snapshot,
+        &newer_snapshot,
+        &paired_new,
+        &mut violations,
+    );
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_support;
+    use crate::snapshot::snapshot_string_for_testing;
+
+    #[test]
+    fn enter_pairs_identically_checksummed_renames_as_moved() {
+        let options = test_support::options(None, Some("nodelete,noadd"));
+        let config = Config::new(&options).unwrap();
+
+        let older_snapshot = Snapshot::new(&snapshot_string_for_testing(
+            r#"0000000000000000000000000000000000000000000000000000000000000001  ./old/path.txt
+"#,
+        ))
+        .unwrap();
+        let newer_snapshot = Snapshot::new(&snapshot_string_for_testing(
+            r#"0000000000000000000000000000000000000000000000000000000000000001  ./new/path.txt
+"#,
+        ))
+        .unwrap();
 
-    violations
+        let violations = enter(&config, &older_snapshot, &newer_snapshot).unwrap();
+        assert_eq!(
+            format!("{}", violations),
+            "-> ./old/path.txt => ./new/path.txt\n"
+        );
+    }
+
+    #[test]
+    fn enter_does_not_pair_moves_under_nomove_policy() {
+        let options = test_support::options(None, Some("nodelete,noadd,nomove"));
+        let config = Config::new(&options).unwrap();
+
+        let older_snapshot = Snapshot::new(&snapshot_string_for_testing(
+            r#"0000000000000000000000000000000000000000000000000000000000000001  ./old/path.txt
+"#,
+        ))
+        .unwrap();
+        let newer_snapshot = Snapshot::new(&snapshot_string_for_testing(
+            r#"0000000000000000000000000000000000000000000000000000000000000001  ./new/path.txt
+"#,
+        ))
+        .unwrap();
+
+        let violations = enter(&config, &older_snapshot, &newer_snapshot).unwrap();
+        assert_eq!(
+            format!("{}", violations),
+            "+ ./new/path.txt\n- ./old/path.txt\n"
+        );
+    }
+
+    #[test]
+    fn enter_rejects_mismatched_checksum_algorithms() {
+        let options = test_support::options(None, None);
+        let config = Config::new(&options).unwrap();
+
+        let older_snapshot = Snapshot::new(&snapshot_string_for_testing(
+            "0000000000000000000000000000000000000000000000000000000000000001  ./path.txt",
+        ))
+        .unwrap();
+        let sha512_checksum = "0".repeat(128);
+        let newer_snapshot = Snapshot::new(&snapshot_string_for_testing(&format!(
+            "{}  ./path.txt",
+            sha512_checksum
+        )))
+        .unwrap();
+
+        match enter(&config, &older_snapshot, &newer_snapshot).unwrap_err() {
+            ZakocmpError::Snapshot(message) => {
+                assert!(message.starts_with("snapshots use different checksum algorithms"))
+            }
+            _ => panic!("expected ZakocmpError::Snapshot"),
+        };
+    }
 }
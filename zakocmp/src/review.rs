@@ -0,0 +1,45 @@
+// This module implements zakocmp's interactive review mode: each
+// flagged path is handed off to an external viewer (the configured
+// review command, `$PAGER` by default) so a reviewer can eyeball the
+// underlying change before deciding whether to accept it into the
+// baseline, much like `insta` spawning the user's configured viewer
+// during review.
+
+use crate::structs::ZakocmpError;
+
+// Returns the default review command: the value of $PAGER, falling
+// back to `less` if unset.
+pub fn default_review_command() -> String {
+    std::env::var("PAGER").unwrap_or_else(|_| "less".to_string())
+}
+
+// Invokes `command` on `path` and waits for it to exit. `command` is
+// split on whitespace into a program and its leading arguments (e.g.
+// `PAGER="less -R"`), so a configured command with flags spawns
+// correctly instead of being looked up as one literal (and
+// nonexistent) binary name. If the requested tool isn't available on
+// this platform, falls back to just printing the path rather than
+// aborting the whole review; any other spawn failure propagates as a
+// ZakocmpError::Io.
+pub fn show_path(command: &str, path: &str) -> Result<(), ZakocmpError> {
+    let mut tokens = command.split_whitespace();
+    let program = match tokens.next() {
+        Some(program) => program,
+        None => {
+            println!("{}", path);
+            return Ok(());
+        }
+    };
+    match std::process::Command::new(program)
+        .args(tokens)
+        .arg(path)
+        .status()
+    {
+        Ok(_) => Ok(()),
+        Err(ref io_error) if io_error.kind() == std::io::ErrorKind::NotFound => {
+            println!("{}", path);
+            Ok(())
+        }
+        Err(io_error) => Err(ZakocmpError::Io(io_error)),
+    }
+}
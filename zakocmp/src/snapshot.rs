@@ -4,16 +4,98 @@
 
 use std::collections::HashMap;
 use std::result::Result;
-use std::str::Lines;
 use std::string::String;
 
-use crate::errors::ZakocmpError;
+use crate::structs::ZakocmpError;
+use crate::json_snapshot::JsonSnapshot;
 
 // Defines the number of lines preceding the actual checksum content.
 const HEADER_LINES: usize = 3;
 
-// Defines the number of hex characters in a sha256sum.
-const CHECKSUM_CHARS: usize = 64;
+// Enumerates the digest algorithms zakocmp can recognize in a
+// snapshot's checksum lines, identified by the hex width they emit.
+// sha256 and blake3 both produce 64 hex characters; when the header
+// doesn't otherwise disambiguate, a 64-character digest is assumed to
+// be sha256.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl Algorithm {
+    // The number of hex characters a digest produced by this algorithm
+    // occupies.
+    fn width(&self) -> usize {
+        match self {
+            Algorithm::Md5 => 32,
+            Algorithm::Sha1 => 40,
+            Algorithm::Sha256 | Algorithm::Blake3 => 64,
+            Algorithm::Sha512 => 128,
+        }
+    }
+
+    // The name used in error messages and snapshot headers.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "md5",
+            Algorithm::Sha1 => "sha1",
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Sha512 => "sha512",
+            Algorithm::Blake3 => "blake3",
+        }
+    }
+
+    // The inverse of `name()`. Returns `None` for anything
+    // unrecognized. A structured text header treats that the same as
+    // an absent key (falling back to width detection); a JSON snapshot
+    // rejects it as an error.
+    fn from_name(name: &str) -> Option<Algorithm> {
+        match name {
+            "md5" => Some(Algorithm::Md5),
+            "sha1" => Some(Algorithm::Sha1),
+            "sha256" => Some(Algorithm::Sha256),
+            "sha512" => Some(Algorithm::Sha512),
+            "blake3" => Some(Algorithm::Blake3),
+            _ => None,
+        }
+    }
+}
+
+// Returns the length of the leading run of hex digits in `line`.
+fn leading_hex_width(line: &str) -> usize {
+    line.find(|c: char| !c.is_ascii_hexdigit())
+        .unwrap_or_else(|| line.len())
+}
+
+// Inspects the first content line of a snapshot (plus its header, for
+// disambiguation) and guesses which digest algorithm produced it.
+// Anything inconclusive - an empty snapshot, an unrecognized width, a
+// malformed line - falls back to sha256/64, which preserves this
+// module's historical (and still most common) behavior; the
+// resulting width mismatch is what actually surfaces as a "malformed
+// snapshot line" error once parsing proper begins.
+fn detect_algorithm(first_content_line: Option<&str>, header: &str) -> Algorithm {
+    let first_content_line = match first_content_line {
+        Some(line) => line,
+        None => return Algorithm::Sha256,
+    };
+    let width = leading_hex_width(first_content_line);
+    if !first_content_line[width..].starts_with("  ") {
+        return Algorithm::Sha256;
+    }
+    match width {
+        32 => Algorithm::Md5,
+        40 => Algorithm::Sha1,
+        64 if header.to_lowercase().contains("blake3") => Algorithm::Blake3,
+        64 => Algorithm::Sha256,
+        128 => Algorithm::Sha512,
+        _ => Algorithm::Sha256,
+    }
+}
 
 // Defines a valid zakopane snapshot header.
 const SNAPSHOT_HEADER_FOR_TESTING: &'static str = r#"simple-zakopane.sh: 2019-07-27-090032
@@ -21,6 +103,27 @@ simple-zakopane.sh: /home/kalvin
 # this line is typically empty but must be present
 "#;
 
+// Marks the start of the newer, structured (versioned) snapshot
+// header, as opposed to the legacy free-form 3-line header above.
+// Inspired by insta's structured `MetaData` block, this lets a
+// snapshot declare its digest algorithm, scan root, and timestamp as
+// parseable key/value lines instead of leaving them to guesswork.
+const STRUCTURED_HEADER_PREFIX: &'static str = "zakopane-version: ";
+const ALGO_KEY: &'static str = "zakopane-algo: ";
+const ROOT_KEY: &'static str = "zakopane-root: ";
+const DATE_KEY: &'static str = "zakopane-date: ";
+
+// Metadata recovered from a structured header. Every field defaults to
+// `None` so a legacy (or partially-written) header just means the
+// corresponding `Snapshot` accessor reports nothing, rather than an
+// error.
+#[derive(Debug, Default)]
+struct HeaderMetadata {
+    algorithm: Option<Algorithm>,
+    root: Option<String>,
+    timestamp: Option<String>,
+}
+
 // Accepts a borrowed string representation of some zakopane
 // checksums, prepends the standard zakopane snapshot header to the
 // same, and returns the owned result.
@@ -34,65 +137,175 @@ pub fn snapshot_string_for_testing(checksums: &str) -> String {
 #[derive(Debug)]
 pub struct Snapshot {
     contents: HashMap<String, String>,
+    algorithm: Algorithm,
+    // The scan root this snapshot was taken against, if its header was
+    // structured (`zakopane-root: ...`). `None` for legacy snapshots.
+    root: Option<String>,
+    // This snapshot's declared timestamp (`zakopane-date: ...`).
+    // `None` for legacy snapshots.
+    timestamp: Option<String>,
 }
 
 // Borrows the string representation of a line in a zakopane snapshot
 // and returns sliced str's in a tuple of (checksum, path).
-fn parse_snapshot_line(line: &str) -> Result<(&str, &str), ZakocmpError> {
+// `checksum_chars` is the hex width this snapshot's algorithm produces.
+fn parse_snapshot_line(line: &str, checksum_chars: usize) -> Result<(&str, &str), ZakocmpError> {
     let bad_line = ZakocmpError::Snapshot(format!("malformed snapshot line: ``{}''", line));
     // A snapshot line should consist of the checksum, two spaces, and a
     // non-empty pathname.
-    if line.len() < CHECKSUM_CHARS + 3
-        || !line.is_char_boundary(CHECKSUM_CHARS)
-        || !line.is_char_boundary(CHECKSUM_CHARS + 1)
-        || !line.is_char_boundary(CHECKSUM_CHARS + 2)
+    if line.len() < checksum_chars + 3
+        || !line.is_char_boundary(checksum_chars)
+        || !line.is_char_boundary(checksum_chars + 1)
+        || !line.is_char_boundary(checksum_chars + 2)
     {
         return Err(bad_line);
     }
 
-    let (checksum, path_with_leading_space) = line.split_at(CHECKSUM_CHARS);
+    let (checksum, path_with_leading_space) = line.split_at(checksum_chars);
     if !path_with_leading_space.starts_with("  ") {
         return Err(bad_line);
     }
     Ok((checksum, &path_with_leading_space[2..]))
 }
 
+// Peeks (without consuming) at `reader`'s leading bytes to decide
+// whether it holds a JSON snapshot, identified by a `{` as the first
+// non-whitespace byte. The only bytes consumed are buffers made up
+// entirely of leading whitespace; everything from the first
+// non-whitespace byte on is left in `reader` so the caller can still
+// parse it in full.
+fn starts_with_json_object<R: std::io::BufRead>(reader: &mut R) -> Result<bool, ZakocmpError> {
+    loop {
+        let buf = reader.fill_buf().map_err(ZakocmpError::Io)?;
+        match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(index) => return Ok(buf[index] == b'{'),
+            None if buf.is_empty() => return Ok(false),
+            None => {
+                let len = buf.len();
+                reader.consume(len);
+            }
+        }
+    }
+}
+
 impl Snapshot {
     // Borrows the string representation of a zakopane snapshot and
-    // returns the corresponding Snapshot struct.
+    // returns the corresponding Snapshot struct. A thin wrapper around
+    // `from_reader`, kept around because most callers (and all of this
+    // module's tests) already have the whole snapshot in memory as a
+    // `&str`.
     pub fn new(snapshot: &str) -> Result<Snapshot, ZakocmpError> {
-        let mut lines: Lines = snapshot.lines();
-
-        // A zakopane snapshot starts with three extra lines intended
-        // for human readers. zakocmp doesn't care about this header.
-        let mut header_drain: usize = HEADER_LINES;
-        while header_drain > 0 {
-            match lines.next() {
-                Some(_) => (),
-                None => {
-                    return Err(ZakocmpError::Snapshot(
-                        "truncated zakopane snapshot".to_string(),
-                    ))
-                }
-            };
-            header_drain -= 1;
+        Snapshot::from_reader(snapshot.as_bytes())
+    }
+
+    // Reads a zakopane snapshot incrementally from any `BufRead` (e.g.
+    // a `BufReader` wrapped around an open file), instead of requiring
+    // the whole snapshot to be slurped into memory first. Lines are
+    // consumed one at a time; a malformed or truncated snapshot fails
+    // as soon as the offending line is reached.
+    //
+    // Transparently dispatches to the JSON snapshot format (see
+    // `json_snapshot`) when the first non-whitespace byte is `{`,
+    // falling back to the legacy/structured text grammar otherwise.
+    pub fn from_reader<R: std::io::BufRead>(mut reader: R) -> Result<Snapshot, ZakocmpError> {
+        if starts_with_json_object(&mut reader)? {
+            return Snapshot::from_json_reader(reader);
         }
 
-        // Ingests the rest of the snapshot representation.
+        let truncated = || ZakocmpError::Snapshot("truncated zakopane snapshot".to_string());
+        let mut lines = reader.lines();
+        let next_line = |lines: &mut std::io::Lines<R>| -> Result<String, ZakocmpError> {
+            lines.next().ok_or_else(truncated)?.map_err(ZakocmpError::Io)
+        };
+
+        // A zakopane snapshot starts with either the legacy three-line
+        // free-form header or the newer structured header.
+        let first_line = next_line(&mut lines)?;
+        let (header_metadata, header) = if first_line.starts_with(STRUCTURED_HEADER_PREFIX) {
+            let mut metadata = HeaderMetadata::default();
+            for _ in 0..3 {
+                let line = next_line(&mut lines)?;
+                if let Some(value) = line.strip_prefix(ALGO_KEY) {
+                    metadata.algorithm = Algorithm::from_name(value);
+                } else if let Some(value) = line.strip_prefix(ROOT_KEY) {
+                    metadata.root = Some(value.to_string());
+                } else if let Some(value) = line.strip_prefix(DATE_KEY) {
+                    metadata.timestamp = Some(value.to_string());
+                }
+            }
+            (metadata, String::new())
+        } else {
+            // zakocmp doesn't care about the legacy header's content
+            // beyond using it to help disambiguate the digest
+            // algorithm below.
+            let mut header_lines = vec![first_line];
+            for _ in 0..HEADER_LINES - 1 {
+                header_lines.push(next_line(&mut lines)?);
+            }
+            (HeaderMetadata::default(), header_lines.join("\n"))
+        };
+
+        // A `BufRead` can't be peeked a whole line at a time without
+        // consuming that line, so the first content line
+        // (if any) is read up front purely to disambiguate the digest
+        // algorithm, then fed into the ingestion loop below just like
+        // every other line.
+        let first_content_line = lines.next().transpose().map_err(ZakocmpError::Io)?;
+        let algorithm = header_metadata
+            .algorithm
+            .unwrap_or_else(|| detect_algorithm(first_content_line.as_deref(), &header));
+        let checksum_chars = algorithm.width();
+
         let mut contents: HashMap<String, String> = HashMap::new();
-        for line in lines {
-            let (checksum, path) = parse_snapshot_line(line)?;
+        let ingest_line = |line: &str, contents: &mut HashMap<String, String>| {
+            let (checksum, path) = parse_snapshot_line(line, checksum_chars)?;
             match contents.insert(path.to_string(), checksum.to_string()) {
-                None => (),
-                Some(old_checksum) => {
-                    return Err(ZakocmpError::Snapshot(format!(
-                        "path collision: {} (was already {}, is now {})",
-                        path, old_checksum, checksum
-                    )))
-                }
-            };
+                None => Ok(()),
+                Some(old_checksum) => Err(ZakocmpError::Snapshot(format!(
+                    "path collision: {} (was already {}, is now {})",
+                    path, old_checksum, checksum
+                ))),
+            }
+        };
+        if let Some(line) = &first_content_line {
+            ingest_line(line, &mut contents)?;
+        }
+        for line in lines {
+            let line = line.map_err(ZakocmpError::Io)?;
+            ingest_line(&line, &mut contents)?;
         }
-        Ok(Snapshot { contents: contents })
+        Ok(Snapshot {
+            contents,
+            algorithm,
+            root: header_metadata.root,
+            timestamp: header_metadata.timestamp,
+        })
+    }
+
+    // Reads a JSON snapshot (see `json_snapshot`) from `reader`.
+    // Doesn't require slurping `reader` into a `String` first -
+    // `serde_json::from_reader` parses directly off the stream.
+    fn from_json_reader<R: std::io::BufRead>(reader: R) -> Result<Snapshot, ZakocmpError> {
+        let parsed: JsonSnapshot = serde_json::from_reader(reader)
+            .map_err(|e| ZakocmpError::Snapshot(format!("malformed JSON snapshot: {}", e)))?;
+        if parsed.version != crate::json_snapshot::JSON_SNAPSHOT_VERSION {
+            return Err(ZakocmpError::Snapshot(format!(
+                "unsupported JSON snapshot version: {}",
+                parsed.version
+            )));
+        }
+        let algorithm = Algorithm::from_name(&parsed.algorithm).ok_or_else(|| {
+            ZakocmpError::Snapshot(format!(
+                "unrecognized checksum algorithm: ``{}''",
+                parsed.algorithm
+            ))
+        })?;
+        Ok(Snapshot {
+            contents: parsed.contents,
+            algorithm,
+            root: parsed.root,
+            timestamp: parsed.timestamp,
+        })
     }
 
     // Passes the inner struct's iterator straight out.
@@ -104,6 +317,53 @@ impl Snapshot {
     pub fn get(&self, key: &str) -> std::option::Option<&String> {
         self.contents.get(key)
     }
+
+    // Returns the digest algorithm this snapshot's checksums were
+    // computed with.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    // Returns the scan root this snapshot was taken against, if its
+    // header declared one. `None` for legacy (unstructured) snapshots.
+    pub fn root(&self) -> Option<&str> {
+        self.root.as_deref()
+    }
+
+    // Returns this snapshot's declared timestamp, if its header
+    // declared one. `None` for legacy (unstructured) snapshots.
+    pub fn timestamp(&self) -> Option<&str> {
+        self.timestamp.as_deref()
+    }
+}
+
+// Rejects a pair of snapshots built with different digest algorithms;
+// comparing their checksums path-for-path would otherwise silently
+// report every file as modified.
+pub fn ensure_same_algorithm(a: &Snapshot, b: &Snapshot) -> Result<(), ZakocmpError> {
+    if a.algorithm != b.algorithm {
+        return Err(ZakocmpError::Snapshot(format!(
+            "snapshots use different checksum algorithms: {} vs {}",
+            a.algorithm.name(),
+            b.algorithm.name()
+        )));
+    }
+    Ok(())
+}
+
+// Returns a warning when both snapshots declare a scan root (via the
+// structured header) and those roots disagree. Returns `None` when
+// either snapshot lacks root metadata (e.g. a legacy snapshot) or when
+// the roots match, since comparing across roots isn't necessarily
+// wrong - just worth flagging.
+pub fn root_mismatch_warning(a: &Snapshot, b: &Snapshot) -> Option<String> {
+    match (&a.root, &b.root) {
+        (Some(root_a), Some(root_b)) if root_a != root_b => Some(format!(
+            "warning: snapshots were taken at different roots: {} vs {}",
+            root_a, root_b
+        )),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
@@ -222,4 +482,247 @@ simple-zakopane.sh: /home/kalvin
         // sequence of bytes.
         assert!(snapshot.get("a/bold-one.txt").is_none());
     }
+
+    #[test]
+    fn snapshot_from_reader_matches_snapshot_new() {
+        let text = snapshot_string_for_testing(
+            r#"4e8401b759a877c0d215ba95bb75bd7d08318cbdc395b3fae9763337ee3614a5  ./hello/there.txt
+"#,
+        );
+        let from_str = Snapshot::new(&text).unwrap();
+        let from_reader = Snapshot::from_reader(text.as_bytes()).unwrap();
+
+        assert_eq!(from_reader.algorithm(), from_str.algorithm());
+        assert_eq!(
+            from_reader.get("./hello/there.txt"),
+            from_str.get("./hello/there.txt")
+        );
+    }
+
+    #[test]
+    fn snapshot_from_reader_propagates_malformed_lines() {
+        let text = snapshot_string_for_testing("not a checksum line");
+        assert_snapshot_error(
+            Snapshot::from_reader(text.as_bytes()).unwrap_err(),
+            "malformed",
+        );
+    }
+
+    #[test]
+    fn snapshot_from_reader_propagates_truncation() {
+        let text = "simple-zakopane.sh: 2019-07-27-090032\n";
+        assert_snapshot_error(
+            Snapshot::from_reader(text.as_bytes()).unwrap_err(),
+            "truncated",
+        );
+    }
+
+    #[test]
+    fn snapshot_detects_algorithm_from_checksum_width() {
+        let sha256 = Snapshot::new(&snapshot_string_for_testing(
+            "4e8401b759a877c0d215ba95bb75bd7d08318cbdc395b3fae9763337ee3614a5  ./hello/there.txt",
+        ))
+        .unwrap();
+        assert_eq!(sha256.algorithm(), Algorithm::Sha256);
+
+        let md5_checksum = "0".repeat(32);
+        let md5 = Snapshot::new(&snapshot_string_for_testing(&format!(
+            "{}  ./hello/there.txt",
+            md5_checksum
+        )))
+        .unwrap();
+        assert_eq!(md5.algorithm(), Algorithm::Md5);
+
+        let sha1_checksum = "0".repeat(40);
+        let sha1 = Snapshot::new(&snapshot_string_for_testing(&format!(
+            "{}  ./hello/there.txt",
+            sha1_checksum
+        )))
+        .unwrap();
+        assert_eq!(sha1.algorithm(), Algorithm::Sha1);
+
+        let sha512_checksum = "0".repeat(128);
+        let sha512 = Snapshot::new(&snapshot_string_for_testing(&format!(
+            "{}  ./hello/there.txt",
+            sha512_checksum
+        )))
+        .unwrap();
+        assert_eq!(sha512.algorithm(), Algorithm::Sha512);
+
+        // An empty snapshot has nothing to detect from, so it defaults
+        // to the historical assumption of sha256.
+        let empty = Snapshot::new(SNAPSHOT_HEADER_FOR_TESTING).unwrap();
+        assert_eq!(empty.algorithm(), Algorithm::Sha256);
+    }
+
+    #[test]
+    fn snapshot_disambiguates_blake3_via_header_hint() {
+        let blake3_header = "simple-zakopane.sh: 2019-07-27-090032\n\
+             simple-zakopane.sh: /home/kalvin\n\
+             # digest algorithm: blake3\n";
+        let blake3_checksum = "0".repeat(64);
+        let snapshot = Snapshot::new(&format!(
+            "{}{}  ./hello/there.txt\n",
+            blake3_header, blake3_checksum
+        ))
+        .unwrap();
+        assert_eq!(snapshot.algorithm(), Algorithm::Blake3);
+    }
+
+    #[test]
+    fn snapshot_parses_structured_header() {
+        let header = r#"zakopane-version: 1
+zakopane-algo: blake3
+zakopane-root: /home/kalvin
+zakopane-date: 2019-07-27-090032
+"#;
+        let checksum = "0".repeat(64);
+        let snapshot = Snapshot::new(&format!(
+            "{}{}  ./hello/there.txt\n",
+            header, checksum
+        ))
+        .unwrap();
+
+        assert_eq!(snapshot.algorithm(), Algorithm::Blake3);
+        assert_eq!(snapshot.root().unwrap(), "/home/kalvin");
+        assert_eq!(snapshot.timestamp().unwrap(), "2019-07-27-090032");
+    }
+
+    #[test]
+    fn snapshot_structured_header_keys_may_appear_in_any_order() {
+        let header = r#"zakopane-version: 1
+zakopane-date: 2019-07-27-090032
+zakopane-root: /home/kalvin
+zakopane-algo: sha512
+"#;
+        let checksum = "0".repeat(128);
+        let snapshot = Snapshot::new(&format!(
+            "{}{}  ./hello/there.txt\n",
+            header, checksum
+        ))
+        .unwrap();
+
+        assert_eq!(snapshot.algorithm(), Algorithm::Sha512);
+        assert_eq!(snapshot.root().unwrap(), "/home/kalvin");
+        assert_eq!(snapshot.timestamp().unwrap(), "2019-07-27-090032");
+    }
+
+    #[test]
+    fn snapshot_legacy_header_leaves_root_and_timestamp_unset() {
+        let snapshot = Snapshot::new(SNAPSHOT_HEADER_FOR_TESTING).unwrap();
+        assert!(snapshot.root().is_none());
+        assert!(snapshot.timestamp().is_none());
+    }
+
+    #[test]
+    fn snapshot_structured_header_rejects_truncation() {
+        let truncated = "zakopane-version: 1\nzakopane-algo: sha256\n";
+        assert_snapshot_error(Snapshot::new(truncated).unwrap_err(), "truncated");
+    }
+
+    #[test]
+    fn root_mismatch_warning_flags_differing_roots() {
+        let structured = |root: &str| {
+            Snapshot::new(&format!(
+                "zakopane-version: 1\n\
+                 zakopane-algo: sha256\n\
+                 zakopane-root: {}\n\
+                 zakopane-date: 2019-07-27-090032\n\
+                 4e8401b759a877c0d215ba95bb75bd7d08318cbdc395b3fae9763337ee3614a5  \
+                 ./hello/there.txt\n",
+                root
+            ))
+            .unwrap()
+        };
+
+        let a = structured("/home/kalvin");
+        let b = structured("/home/kalvin");
+        assert!(root_mismatch_warning(&a, &b).is_none());
+
+        let c = structured("/home/someone-else");
+        assert!(root_mismatch_warning(&a, &c)
+            .unwrap()
+            .starts_with("warning: snapshots were taken at different roots"));
+
+        // Legacy snapshots have no declared root, so there's nothing
+        // to compare - no warning either way.
+        let legacy = Snapshot::new(&snapshot_string_for_testing(
+            "4e8401b759a877c0d215ba95bb75bd7d08318cbdc395b3fae9763337ee3614a5  ./hello/there.txt",
+        ))
+        .unwrap();
+        assert!(root_mismatch_warning(&a, &legacy).is_none());
+    }
+
+    #[test]
+    fn ensure_same_algorithm_rejects_mismatched_snapshots() {
+        let sha256 = Snapshot::new(&snapshot_string_for_testing(
+            "4e8401b759a877c0d215ba95bb75bd7d08318cbdc395b3fae9763337ee3614a5  ./hello/there.txt",
+        ))
+        .unwrap();
+        let sha512_checksum = "0".repeat(128);
+        let sha512 = Snapshot::new(&snapshot_string_for_testing(&format!(
+            "{}  ./hello/there.txt",
+            sha512_checksum
+        )))
+        .unwrap();
+
+        assert_snapshot_error(
+            ensure_same_algorithm(&sha256, &sha512).unwrap_err(),
+            "snapshots use different checksum algorithms",
+        );
+        assert!(ensure_same_algorithm(&sha256, &sha256).is_ok());
+    }
+
+    #[test]
+    fn snapshot_parses_json_format() {
+        let json = r#"{
+            "version": 1,
+            "algorithm": "sha256",
+            "root": "/home/kalvin",
+            "timestamp": "2019-07-27-090032",
+            "contents": {
+                "./hello/there.txt": "4e8401b759a877c0d215ba95bb75bd7d08318cbdc395b3fae9763337ee3614a5"
+            }
+        }"#;
+        let snapshot = Snapshot::new(json).unwrap();
+        assert_eq!(snapshot.algorithm(), Algorithm::Sha256);
+        assert_eq!(snapshot.root().unwrap(), "/home/kalvin");
+        assert_eq!(snapshot.timestamp().unwrap(), "2019-07-27-090032");
+        assert_eq!(
+            snapshot.get("./hello/there.txt").unwrap(),
+            "4e8401b759a877c0d215ba95bb75bd7d08318cbdc395b3fae9763337ee3614a5"
+        );
+    }
+
+    #[test]
+    fn snapshot_json_detection_tolerates_leading_whitespace() {
+        let json = "  \n\t{\"version\": 1, \"algorithm\": \"sha256\", \"contents\": {}}";
+        let snapshot = Snapshot::new(json).unwrap();
+        assert_eq!(snapshot.algorithm(), Algorithm::Sha256);
+        assert!(snapshot.root().is_none());
+    }
+
+    #[test]
+    fn snapshot_json_rejects_unsupported_version() {
+        let json = r#"{"version": 2, "algorithm": "sha256", "contents": {}}"#;
+        assert_snapshot_error(
+            Snapshot::new(json).unwrap_err(),
+            "unsupported JSON snapshot version",
+        );
+    }
+
+    #[test]
+    fn snapshot_json_rejects_unrecognized_algorithm() {
+        let json = r#"{"version": 1, "algorithm": "sha999", "contents": {}}"#;
+        assert_snapshot_error(
+            Snapshot::new(json).unwrap_err(),
+            "unrecognized checksum algorithm",
+        );
+    }
+
+    #[test]
+    fn snapshot_json_rejects_malformed_json() {
+        let json = r#"{"version": 1"#;
+        assert_snapshot_error(Snapshot::new(json).unwrap_err(), "malformed JSON snapshot");
+    }
 }
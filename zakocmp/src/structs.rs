@@ -5,12 +5,28 @@
 pub enum ZakocmpError {
     // Propagates I/O errors (e.g. from reading actual files).
     Io(std::io::Error),
+    // Propagates an I/O error together with the path that triggered
+    // it, so a failure to open one of several config/snapshot/baseline
+    // paths says which one was at fault instead of a bare "No such
+    // file or directory".
+    IoWithPath(std::path::PathBuf, std::io::Error),
+    // Describes a failure to set up transparent decompression for a
+    // compressed snapshot or config file (e.g. a corrupt zstd frame
+    // header). Plain I/O failures during decompressed reads still
+    // propagate as `IoWithPath`.
+    Decompress(String),
     // Describes problems with zakocmp configuration files.
     Config(String),
+    // Collects every error accumulated while walking a config's whole
+    // `policies` map, so a config with several malformed rules is
+    // reported all at once instead of one edit-run cycle per typo.
+    Multiple(Vec<ZakocmpError>),
     // Describes problems with zakocmp snapshot files.
     Snapshot(String),
     // Describes invalid command-line invocations.
     CommandLine(String),
+    // Describes problems with zakocmp baseline files.
+    Baseline(String),
     // Describes unknown or unspecified errors.
     Unknown(String),
 }
@@ -19,9 +35,18 @@ impl std::fmt::Display for ZakocmpError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ZakocmpError::Io(io_error) => write!(f, "{}", io_error.to_string()),
-            ZakocmpError::Config(message)
+            ZakocmpError::IoWithPath(path, io_error) => {
+                write!(f, "failed to read \"{}\": {}", path.display(), io_error)
+            }
+            ZakocmpError::Multiple(errors) => {
+                let messages: Vec<String> = errors.iter().map(ZakocmpError::to_string).collect();
+                write!(f, "{}", messages.join("\n"))
+            }
+            ZakocmpError::Decompress(message)
+            | ZakocmpError::Config(message)
             | ZakocmpError::Snapshot(message)
             | ZakocmpError::CommandLine(message)
+            | ZakocmpError::Baseline(message)
             | ZakocmpError::Unknown(message) => write!(f, "{}", message),
         }
     }
@@ -36,4 +61,56 @@ pub struct CliOptions<'a> {
     pub config_path: Option<&'a str>,
     // A default policy on the command-line is optional.
     pub default_policy: Option<&'a str>,
+    // Path to a baseline file of previously-acknowledged violations.
+    // When present, violations matching an entry here are suppressed
+    // from the "new" report.
+    pub baseline_path: Option<&'a str>,
+    // When set, instead of comparing against the baseline, overwrite
+    // it with the violations produced by this run.
+    pub write_baseline: bool,
+    // Selects how violations are printed to stdout.
+    pub output_format: OutputFormat,
+    // When set, walks the new violations one at a time, showing each
+    // flagged path with `review_command` and prompting the operator to
+    // accept it into the baseline or leave it outstanding.
+    pub review: bool,
+    // The external command used to show a flagged path during review.
+    // Defaults to `$PAGER` (or `less`) when unset.
+    pub review_command: Option<&'a str>,
+    // When set (`--quiet`/`--check`), suppresses all stdout; only the
+    // exit code reports whether violations were found. Meant for
+    // pre-commit hooks and cron jobs that only care about the status.
+    pub quiet: bool,
+}
+
+// Selects how a `Violations` report is presented. Borrows the idea
+// behind insta's `OutputBehavior` enum (Diff, Summary, Minimal,
+// Nothing).
+#[derive(Debug, PartialEq)]
+pub enum OutputFormat {
+    // Today's `+ ! -` per-path listing.
+    Full,
+    // Just the per-kind violation counts, one line.
+    Summary,
+    // Nothing at all unless violations were found, then a single line
+    // giving the total count.
+    Minimal,
+    // A JSON object of violations plus a per-kind summary count.
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ZakocmpError;
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        match token {
+            "full" => Ok(OutputFormat::Full),
+            "summary" => Ok(OutputFormat::Summary),
+            "minimal" => Ok(OutputFormat::Minimal),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(ZakocmpError::CommandLine(format!(
+                "bad output format: ``{}''",
+                token
+            ))),
+        }
+    }
 }
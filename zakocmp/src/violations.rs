@@ -2,18 +2,29 @@
 // violations - i.e. human-readable descriptions of notable
 // discrepancies between zakocmp snapshots.
 
-use crate::errors::ZakocmpError;
+use crate::structs::ZakocmpError;
 
 // Represents possible policy violations as caller-passable ints.
 pub const ADDED: i32 = 0;
 pub const DELETED: i32 = 1 << 0;
 pub const MODIFIED: i32 = 1 << 1;
+// A path was deleted on one side and an identically-checksummed path
+// was added on the other; `compare` pairs these instead of reporting
+// a bare `+`/`-`.
+pub const MOVED: i32 = 1 << 2;
 
 // Represents human-readable counterparts to the above. These are meant
 // for printing etc. and so are not relevant to callers.
 const REPR_ADDED: &'static str = "+";
 const REPR_DELETED: &'static str = "-";
 const REPR_MODIFIED: &'static str = "!";
+const REPR_MOVED: &'static str = "->";
+
+// Represents the `kind` field emitted by `Violations::to_json`.
+const JSON_KIND_ADDED: &'static str = "added";
+const JSON_KIND_DELETED: &'static str = "deleted";
+const JSON_KIND_MODIFIED: &'static str = "modified";
+const JSON_KIND_MOVED: &'static str = "moved";
 
 // A single violation consists of the offending path (arbitrary bytes)
 // and the kind of violation (i32 - as above).
@@ -21,6 +32,23 @@ pub struct Violations {
     data: std::vec::Vec<(String, i32)>,
 }
 
+// Per-kind counts of the violations recorded in a `Violations`. Used
+// by the `summary`/`minimal` output modes, and by `to_json`'s
+// `summary` object.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ViolationCounts {
+    pub added: usize,
+    pub deleted: usize,
+    pub modified: usize,
+    pub moved: usize,
+}
+
+impl ViolationCounts {
+    pub fn total(&self) -> usize {
+        self.added + self.deleted + self.modified + self.moved
+    }
+}
+
 impl Violations {
     pub fn new() -> Violations {
         Violations { data: vec![] }
@@ -36,6 +64,121 @@ impl Violations {
         self.data.push((path.to_owned(), kind));
         Ok(())
     }
+
+    // Records that `old_path` reappeared, unmodified, as `new_path`.
+    pub fn moved(&mut self, old_path: &str, new_path: &str) {
+        self.data
+            .push((format!("{} => {}", old_path, new_path), MOVED));
+    }
+
+    // Splits this Violations into (new, acknowledged) halves according
+    // to whether each (path, kind) pair is already recorded in
+    // `baseline`. Acknowledged entries are the ones a reviewer has
+    // already signed off on and so are demoted out of the "new" half.
+    pub fn partition_against_baseline(
+        &self,
+        baseline: &crate::baseline::Baseline,
+    ) -> (Violations, Violations) {
+        let mut new_violations = Violations::new();
+        let mut acknowledged = Violations::new();
+        for (path, kind) in self.data.iter() {
+            if baseline.contains(path, *kind) {
+                acknowledged.data.push((path.to_owned(), *kind));
+            } else {
+                new_violations.data.push((path.to_owned(), *kind));
+            }
+        }
+        (new_violations, acknowledged)
+    }
+
+    // Returns whether any violations are recorded at all.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // Tallies this Violations's entries by kind, for callers (e.g. the
+    // `summary`/`minimal` output modes) that want structured counts
+    // instead of the full per-path `Display` listing.
+    pub fn counts(&self) -> ViolationCounts {
+        let mut counts = ViolationCounts::default();
+        for (_path, kind) in self.data.iter() {
+            match *kind {
+                ADDED => counts.added += 1,
+                DELETED => counts.deleted += 1,
+                MODIFIED => counts.modified += 1,
+                MOVED => counts.moved += 1,
+                _ => panic!(format!("BUG: bad kind: {}", kind)),
+            };
+        }
+        counts
+    }
+
+    // Returns this Violations's (path, kind) entries in the same sorted
+    // order `Display` uses, for callers (e.g. review mode) that need to
+    // walk entries one at a time instead of printing the whole report.
+    pub fn iter(&self) -> std::vec::IntoIter<(String, i32)> {
+        let mut sorted_violations = self.data.to_owned();
+        sorted_violations.sort_unstable();
+        sorted_violations.into_iter()
+    }
+
+    // Serializes this Violations as a JSON object suitable for
+    // machine consumption: a `violations` array of
+    // `{"path": ..., "kind": "added|deleted|modified|moved"}` records plus a
+    // `summary` object counting each kind. Paths are escaped (not
+    // lossily converted) so byte-exact paths survive the round trip.
+    pub fn to_json(&self) -> String {
+        let mut sorted_violations = self.data.to_owned();
+        sorted_violations.sort_unstable();
+
+        let counts = self.counts();
+        let records: Vec<String> = sorted_violations
+            .iter()
+            .map(|(path, kind)| {
+                format!(
+                    r#"{{"path": "{}", "kind": "{}"}}"#,
+                    json_escape(path),
+                    json_violation_kind(*kind),
+                )
+            })
+            .collect();
+
+        format!(
+            r#"{{"violations": [{}], "summary": {{"added": {}, "deleted": {}, "modified": {}, "moved": {}}}}}"#,
+            records.join(", "),
+            counts.added,
+            counts.deleted,
+            counts.modified,
+            counts.moved,
+        )
+    }
+}
+
+// Escapes `s` for inclusion in a JSON string literal.
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn json_violation_kind(kind: i32) -> &'static str {
+    match kind {
+        ADDED => JSON_KIND_ADDED,
+        DELETED => JSON_KIND_DELETED,
+        MODIFIED => JSON_KIND_MODIFIED,
+        MOVED => JSON_KIND_MOVED,
+        _ => panic!(format!("BUG: bad kind: {}", kind)),
+    }
 }
 
 fn display_violation_type(kind: i32) -> &'static str {
@@ -43,18 +186,37 @@ fn display_violation_type(kind: i32) -> &'static str {
         ADDED => REPR_ADDED,
         DELETED => REPR_DELETED,
         MODIFIED => REPR_MODIFIED,
+        MOVED => REPR_MOVED,
         // This case is serious: the burden is on us to have weeded out
         // invalid violation kinds in previous calls to add().
         _ => panic!(format!("BUG: bad kind: {}", kind)),
     }
 }
 
+// Parses the repr emitted by `display_violation_type` back into its
+// integral violation kind. Returns `None` for anything else, including
+// whitespace.
+pub(crate) fn violation_type_from_repr(repr: &str) -> Option<i32> {
+    match repr {
+        REPR_ADDED => Some(ADDED),
+        REPR_DELETED => Some(DELETED),
+        REPR_MODIFIED => Some(MODIFIED),
+        REPR_MOVED => Some(MOVED),
+        _ => None,
+    }
+}
+
+// Formats a single (path, kind) entry exactly as `Display` would,
+// for callers (e.g. review mode) that consume entries one at a time
+// rather than printing the whole collection.
+pub fn format_entry(path: &str, kind: i32) -> String {
+    format!("{} {}", display_violation_type(kind), path)
+}
+
 impl std::fmt::Display for Violations {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut sorted_violations = self.data.to_owned();
-        sorted_violations.sort_unstable();
-        for (path, kind) in sorted_violations.into_iter() {
-            write!(f, "{} {}\n", display_violation_type(kind), path)?;
+        for (path, kind) in self.iter() {
+            write!(f, "{}\n", format_entry(&path, kind))?;
         }
         Ok(())
     }
@@ -112,4 +274,47 @@ mod tests {
 "#
         );
     }
+
+    #[test]
+    fn violations_counts() {
+        let mut violations = Violations::new();
+        assert_eq!(violations.counts(), ViolationCounts::default());
+
+        assert!(violations.add("a/path", ADDED).is_ok());
+        assert!(violations.add("b/path", ADDED).is_ok());
+        assert!(violations.add("c/path", DELETED).is_ok());
+        assert!(violations.add("d/path", MODIFIED).is_ok());
+        violations.moved("e/path", "f/path");
+
+        let counts = violations.counts();
+        assert_eq!(counts.added, 2);
+        assert_eq!(counts.deleted, 1);
+        assert_eq!(counts.modified, 1);
+        assert_eq!(counts.moved, 1);
+        assert_eq!(counts.total(), 5);
+    }
+
+    #[test]
+    fn violations_moved() {
+        let mut violations = Violations::new();
+        violations.moved("old/path", "new/path");
+        assert_eq!(format!("{}", violations), "-> old/path => new/path\n");
+        assert_eq!(
+            violations.to_json(),
+            r#"{"violations": [{"path": "old/path => new/path", "kind": "moved"}], "summary": {"added": 0, "deleted": 0, "modified": 0, "moved": 1}}"#
+        );
+    }
+
+    #[test]
+    fn violations_to_json() {
+        let mut violations = Violations::new();
+        assert!(violations.add("a/path", ADDED).is_ok());
+        assert!(violations.add("b/path", MODIFIED).is_ok());
+        assert!(violations.add(r#"c/"quoted"/path"#, DELETED).is_ok());
+
+        assert_eq!(
+            violations.to_json(),
+            r#"{"violations": [{"path": "a/path", "kind": "added"}, {"path": "b/path", "kind": "modified"}, {"path": "c/\"quoted\"/path", "kind": "deleted"}], "summary": {"added": 1, "deleted": 1, "modified": 1, "moved": 0}}"#
+        );
+    }
 }
@@ -15,7 +15,7 @@ fn test_basic_default_immutability() {
     // Verifies that empty snapshots never turn up violations.
     let empty_older = Snapshot::new(&snapshot_string_for_testing("")).unwrap();
     let empty_newer = Snapshot::new(&snapshot_string_for_testing("")).unwrap();
-    let empty_violations = libzakocmp::enter(&config, &empty_older, &empty_newer);
+    let empty_violations = libzakocmp::enter(&config, &empty_older, &empty_newer).unwrap();
     assert_eq!(empty_violations.to_string(), "");
 
     // Verifies that disjoint snapshots also violate this policy.
@@ -27,7 +27,7 @@ fn test_basic_default_immutability() {
         "0000000000000000000000000000000000000000000000000000000000000000  ./x/y/z",
     ))
     .unwrap();
-    let disjoint_violations = libzakocmp::enter(&config, &disjoint_older, &disjoint_newer);
+    let disjoint_violations = libzakocmp::enter(&config, &disjoint_older, &disjoint_newer).unwrap();
     // From zakocmp's point of view, ``./a/b/c'' was deleted and
     // ``./x/y/z'' was added.
     assert_eq!(
@@ -64,7 +64,7 @@ fn test_basic_default_immutability() {
         "#
     )))
     .unwrap();
-    let shifty_violations = libzakocmp::enter(&config, &shifty_older, &shifty_newer);
+    let shifty_violations = libzakocmp::enter(&config, &shifty_older, &shifty_newer).unwrap();
     assert_eq!(
         shifty_violations.to_string(),
         indoc!(